@@ -0,0 +1,188 @@
+use crate::device::{Device, Family, RegionKind};
+use crate::hex::memory_image::MemoryImage;
+
+const ERASED_BYTE: u8 = 0xFF;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionReport {
+    pub kind: RegionKind,
+    pub checked: usize,
+    pub non_blank: Vec<(u32, u16)>,
+    pub missing: Vec<u32>,
+}
+
+impl RegionReport {
+    /// A region is blank when every location was read back erased, a region with nothing checked
+    /// is not.
+    pub fn is_blank(&self) -> bool {
+        self.checked > 0 && self.non_blank.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Checks program memory, EEPROM and configuration words of the read-back against their erased
+/// state. Addresses missing from the image are reported as missing, configuration words are
+/// compared to the erased values of the device.
+pub fn check_blank(device: &Device, image: &MemoryImage) -> Vec<RegionReport> {
+    let mut reports = vec![];
+
+    for region in device.regions() {
+        let mut report = RegionReport {
+            kind: region.kind,
+            checked: 0,
+            non_blank: vec![],
+            missing: vec![],
+        };
+
+        match region.kind {
+            RegionKind::Program => {
+                let erased = device.erased_word();
+                for address in region.range.step_by(2) {
+                    match image.word(address) {
+                        Some(word) => {
+                            report.checked += 1;
+                            if word & erased != erased {
+                                report.non_blank.push((address, word));
+                            }
+                        }
+                        None => report.missing.push(address),
+                    }
+                }
+            }
+            RegionKind::Eeprom => {
+                let stride = if device.family == Family::Pic18 { 1 } else { 2 };
+                for address in region.range.step_by(stride) {
+                    match image.bytes.get(&address) {
+                        Some(byte) => {
+                            report.checked += 1;
+                            if *byte != ERASED_BYTE {
+                                report.non_blank.push((address, *byte as u16));
+                            }
+                        }
+                        None => report.missing.push(address),
+                    }
+                }
+            }
+            RegionKind::Config => {
+                for erased in device.erased_config {
+                    match image.word(erased.address) {
+                        Some(word) => {
+                            report.checked += 1;
+                            if !erased.is_erased(word) {
+                                report.non_blank.push((erased.address, word));
+                            }
+                        }
+                        None => report.missing.push(erased.address),
+                    }
+                }
+            }
+            RegionKind::UserId | RegionKind::DeviceId => continue,
+        }
+
+        reports.push(report);
+    }
+
+    reports
+}
+
+#[cfg(test)]
+mod test {
+    use crate::blank_check::check_blank;
+    use crate::device::{Device, Family, RegionKind};
+    use crate::hex::memory_image::MemoryImage;
+
+    fn insert_word(image: &mut MemoryImage, address: u32, word: u16) {
+        let [low, high] = word.to_le_bytes();
+        image.bytes.insert(address, low);
+        image.bytes.insert(address + 1, high);
+    }
+
+    fn erased_image(device: &Device) -> MemoryImage {
+        let mut image = MemoryImage::default();
+        for address in (0..device.program_words * 2).step_by(2) {
+            insert_word(&mut image, address, device.erased_word());
+        }
+        for index in 0..device.eeprom_size {
+            match device.family {
+                Family::Pic18 => {
+                    image.bytes.insert(device.eeprom_address + index, 0xFF);
+                }
+                _ => insert_word(&mut image, device.eeprom_address + index * 2, 0x00FF),
+            }
+        }
+        for erased in device.erased_config {
+            insert_word(&mut image, erased.address, erased.value);
+        }
+        image
+    }
+
+    #[test]
+    fn given_erased_mid_range_device_should_report_all_regions_blank() {
+        let device = Device::find("16F84A").unwrap();
+        let image = erased_image(device);
+
+        let reports = check_blank(device, &image);
+
+        assert!(reports.iter().all(|report| report.is_blank()));
+        assert_eq!(
+            reports.iter().map(|r| r.checked).collect::<Vec<usize>>(),
+            vec![1024, 64, 1]
+        );
+    }
+
+    #[test]
+    fn given_erased_pic18_config_should_compare_to_erased_values() {
+        let device = Device::find("18F2550").unwrap();
+        let mut image = erased_image(device);
+
+        let reports = check_blank(device, &image);
+        assert!(reports.iter().all(|report| report.is_blank()));
+
+        insert_word(&mut image, 0x300000, 0xFFFF);
+        let reports = check_blank(device, &image);
+        assert_eq!(reports[2].non_blank, vec![(0x300000, 0xFFFF)]);
+    }
+
+    #[test]
+    fn given_programmed_words_should_list_their_addresses() {
+        let device = Device::find("16F628A").unwrap();
+        let mut image = erased_image(device);
+        insert_word(&mut image, 0x0002, 0x3055);
+        insert_word(&mut image, 0x4202, 0x0012);
+        insert_word(&mut image, 0x400E, 0x3F14);
+
+        let reports = check_blank(device, &image);
+
+        let non_blank: Vec<(RegionKind, Vec<(u32, u16)>)> = reports
+            .into_iter()
+            .map(|report| (report.kind, report.non_blank))
+            .collect();
+        assert_eq!(
+            non_blank,
+            vec![
+                (RegionKind::Program, vec![(0x0002, 0x3055)]),
+                (RegionKind::Eeprom, vec![(0x4202, 0x0012)]),
+                (RegionKind::Config, vec![(0x400E, 0x3F14)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_missing_addresses_should_not_report_blank() {
+        let device = Device::find("16F628A").unwrap();
+        let mut image = erased_image(device);
+        image.bytes.remove(&0x0010);
+        image.bytes.remove(&0x0011);
+        image
+            .bytes
+            .retain(|address, _| !(0x4200..0x4300).contains(address));
+
+        let reports = check_blank(device, &image);
+
+        assert_eq!(reports[0].missing, vec![0x0010]);
+        assert!(!reports[0].is_blank());
+        assert_eq!(reports[1].checked, 0);
+        assert_eq!(reports[1].missing.len(), 128);
+        assert!(!reports[1].is_blank());
+        assert!(reports[2].is_blank());
+    }
+}
@@ -1,15 +1,324 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use pic_programmer_cli::config_word::{parse_config_override, ConfigOverride};
+use pic_programmer_cli::device::{parse_device, Device};
+use pic_programmer_cli::list_ports::parse_usb_id;
+use pic_programmer_cli::log::{Level, DEFAULT_LEVEL};
+use pic_programmer_cli::programmer::connection::Connection;
+use pic_programmer_cli::programmer::port_selector::parse_port_name;
+use pic_programmer_cli::programmer::resend_policy::ResendPolicy;
+use pic_programmer_cli::programmer::reset::{Reset, ResetLine, ResetPolarity, ResetPulse};
+use pic_programmer_cli::programmer::serial_programmer::EraseMode;
+use pic_programmer_cli::programmer::timeouts::Timeouts;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = ArgAction::Count,
+        help = "Prints more content, -vv also prints every byte exchanged."
+    )]
+    pub verbose: u8,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = ArgAction::Count,
+        conflicts_with = "verbose",
+        help = "Prints only warnings and errors, -qq only errors."
+    )]
+    pub quiet: u8,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Prefixes the console output with UTC timestamps."
+    )]
+    pub timestamps: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Appends every message, whatever the verbosity, with timestamps to this file."
+    )]
+    pub log_file: Option<String>,
+}
+
+impl Cli {
+    pub fn log_level(&self) -> Level {
+        match (self.verbose, self.quiet) {
+            (0, 0) => DEFAULT_LEVEL,
+            (1, _) => Level::Debug,
+            (_, 0) => Level::Trace,
+            (_, 1) => Level::Warn,
+            _ => Level::Error,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ConnectionArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "auto",
+        value_parser = parse_port_name,
+        help = "Port name to use (e.g., COM3), auto probes the ports for the programmer, usb:VID:PID or serial:SERIAL_NUMBER select a USB port."
+    )]
+    pub port_name: String,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = 57600,
+        help = "Baud rate for the connection."
+    )]
+    pub baud_rate: u32,
+
+    #[arg(
+        long,
+        default_value_t = 100,
+        help = "Serial port read and write timeout in milliseconds."
+    )]
+    pub io_timeout: u64,
+
+    #[arg(
+        short = 't',
+        long = "timeout",
+        value_name = "MS",
+        hide = true,
+        value_parser = reject_removed_timeout
+    )]
+    pub removed_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        default_value_t = 10000,
+        help = "Milliseconds to wait for the programmer ready message."
+    )]
+    pub ready_timeout: u64,
+
+    #[arg(
+        long,
+        default_value_t = 5000,
+        help = "Milliseconds to wait for the start message."
+    )]
+    pub start_timeout: u64,
+
+    #[arg(
+        long,
+        default_value_t = 5000,
+        help = "Milliseconds to wait for each line to be acknowledged."
+    )]
+    pub ack_timeout: u64,
+
+    #[arg(
+        long,
+        default_value_t = 30000,
+        help = "Milliseconds to wait for the done message, while reading for the whole read."
+    )]
+    pub done_timeout: u64,
+
+    #[arg(long, help = "Milliseconds the whole session may take.")]
+    pub overall_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        default_value_t = 2500,
+        help = "Milliseconds each port probed by -p auto may take to send the ready message."
+    )]
+    pub detect_timeout: u64,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Identifies the firmware first and stops when it lacks what the command needs."
+    )]
+    pub identify: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Resets the programmer by pulsing this line instead of relying on the auto-reset."
+    )]
+    pub reset_line: Option<ResetLineArg>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ResetPolarityArg::ActiveHigh,
+        help = "Whether the reset pulse asserts (active-high) or releases (active-low) the line."
+    )]
+    pub reset_polarity: ResetPolarityArg,
+
+    #[arg(
+        long,
+        default_value_t = 100,
+        help = "Milliseconds the reset pulse lasts."
+    )]
+    pub reset_duration: u64,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "reset_line",
+        help = "Pings the already running programmer instead of resetting it."
+    )]
+    pub no_reset: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ResetLineArg {
+    Dtr,
+    Rts,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ResetPolarityArg {
+    ActiveHigh,
+    ActiveLow,
+}
+
+impl From<&ConnectionArgs> for Connection {
+    fn from(args: &ConnectionArgs) -> Connection {
+        Connection {
+            port_name: args.port_name.clone(),
+            baud_rate: args.baud_rate,
+            timeout: args.io_timeout,
+            timeouts: Timeouts {
+                ready: Duration::from_millis(args.ready_timeout),
+                start: Duration::from_millis(args.start_timeout),
+                ack: Duration::from_millis(args.ack_timeout),
+                done: Duration::from_millis(args.done_timeout),
+                overall: args.overall_timeout.map(Duration::from_millis),
+                detect: Duration::from_millis(args.detect_timeout),
+            },
+            identify: args.identify,
+            reset: reset(args),
+        }
+    }
+}
+
+/// `-t/--timeout` used to be the only timeout, it is refused rather than silently given the
+/// meaning of `--io-timeout`.
+fn reject_removed_timeout(_: &str) -> Result<u64, String> {
+    Err(String::from(
+        "-t/--timeout was removed, use --io-timeout for the serial port read and write timeout \
+        and --ready-timeout, --start-timeout, --ack-timeout, --done-timeout or --overall-timeout \
+        for the protocol deadlines (see CHANGELOG.md)",
+    ))
+}
+
+fn reset(args: &ConnectionArgs) -> Reset {
+    if args.no_reset {
+        return Reset::Ping;
+    }
+
+    match args.reset_line {
+        Some(line) => Reset::Pulse(ResetPulse {
+            line: match line {
+                ResetLineArg::Dtr => ResetLine::Dtr,
+                ResetLineArg::Rts => ResetLine::Rts,
+            },
+            polarity: match args.reset_polarity {
+                ResetPolarityArg::ActiveHigh => ResetPolarity::ActiveHigh,
+                ResetPolarityArg::ActiveLow => ResetPolarity::ActiveLow,
+            },
+            duration: Duration::from_millis(args.reset_duration),
+        }),
+        None => Reset::Auto,
+    }
+}
+
+#[derive(Args)]
+pub struct ResendArgs {
+    #[arg(
+        long,
+        default_value_t = 5,
+        help = "Maximum number of times a single line is resent."
+    )]
+    pub max_line_resends: u32,
+
+    #[arg(
+        long,
+        default_value_t = 50,
+        help = "Maximum number of resends in the whole programming session."
+    )]
+    pub max_session_resends: u32,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Milliseconds to wait before the first resend of a line."
+    )]
+    pub resend_delay: u64,
+
+    #[arg(
+        long,
+        default_value_t = 2,
+        help = "Factor the resend delay grows by with every further resend of a line."
+    )]
+    pub resend_backoff: u32,
+}
+
+impl From<&ResendArgs> for ResendPolicy {
+    fn from(args: &ResendArgs) -> ResendPolicy {
+        ResendPolicy {
+            per_line: args.max_line_resends,
+            per_session: args.max_session_resends,
+            delay: Duration::from_millis(args.resend_delay),
+            backoff: args.resend_backoff,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum EraseTarget {
+    Bulk,
+    Program,
+    Eeprom,
+}
+
+impl From<EraseTarget> for EraseMode {
+    fn from(target: EraseTarget) -> EraseMode {
+        match target {
+            EraseTarget::Bulk => EraseMode::Bulk,
+            EraseTarget::Program => EraseMode::Program,
+            EraseTarget::Eeprom => EraseMode::Eeprom,
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    ListPorts,
+    ListPorts {
+        #[arg(long, default_value_t = false, help = "Only lists USB ports.")]
+        usb_only: bool,
+
+        #[arg(
+            long,
+            value_parser = parse_usb_id,
+            help = "Only lists USB ports with this hexadecimal vendor id (e.g., 2341)."
+        )]
+        vid: Option<u16>,
+
+        #[arg(
+            long,
+            value_parser = parse_usb_id,
+            help = "Only lists USB ports with this hexadecimal product id (e.g., 0043)."
+        )]
+        pid: Option<u16>,
+
+        #[arg(long, default_value_t = false, help = "Prints the ports as JSON.")]
+        json: bool,
+    },
     Program {
         #[arg(
             short,
@@ -18,49 +327,140 @@ pub enum Commands {
         )]
         input_file_path: String,
 
-        #[arg(short, long, help = "Port name to use (e.g., COM3).")]
-        port_name: String,
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        #[command(flatten)]
+        resend: ResendArgs,
 
         #[arg(
             short,
             long,
-            default_value_t = 57600,
-            help = "Baud rate for the connection."
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
         )]
-        baud_rate: u32,
+        device: Option<&'static Device>,
+
+        #[arg(
+            long = "config",
+            value_parser = parse_config_override,
+            requires = "device",
+            help = "Overrides a configuration field before programming (e.g., WDTE=OFF)."
+        )]
+        config_overrides: Vec<ConfigOverride>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Reads the device back after programming and compares it with the hex file."
+        )]
+        verify: bool,
+    },
+    PrintProgram {
+        #[command(flatten)]
+        connection: ConnectionArgs,
 
         #[arg(
             short,
             long,
-            default_value_t = 5000,
-            help = "Serial port connection timeout in milliseconds."
+            help = "File path where the read program is saved as a hex file."
         )]
-        timeout: u64,
+        output: Option<String>,
 
-        #[arg(short, long, default_value_t = false, help = "Prints more content.")]
-        verbose: bool,
+        #[arg(
+            short,
+            long,
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
+        )]
+        device: Option<&'static Device>,
     },
-    PrintProgram {
-        #[arg(short, long, help = "Port name to use (e.g., COM3).")]
-        port_name: String,
+    Verify {
+        #[arg(
+            short,
+            long,
+            help = "File path to hex file the device is compared against."
+        )]
+        input_file_path: String,
 
+        #[command(flatten)]
+        connection: ConnectionArgs,
+    },
+    Validate {
         #[arg(
             short,
             long,
-            default_value_t = 57600,
-            help = "Baud rate for the connection."
+            help = "File path to hex file that needs to be validated."
         )]
-        baud_rate: u32,
+        input_file_path: String,
 
         #[arg(
             short,
             long,
-            default_value_t = 5000,
-            help = "Serial port connection timeout in milliseconds."
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
         )]
-        timeout: u64,
+        device: Option<&'static Device>,
+    },
+    Config {
+        #[arg(
+            short,
+            long,
+            help = "File path to hex file whose configuration words are decoded."
+        )]
+        input_file_path: String,
 
-        #[arg(short, long, default_value_t = false, help = "Prints more content.")]
-        verbose: bool,
+        #[arg(
+            short,
+            long,
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
+        )]
+        device: &'static Device,
+    },
+    Disasm {
+        #[arg(
+            short,
+            long,
+            help = "File path to hex file, or a saved print-program output, to disassemble."
+        )]
+        input_file_path: String,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
+        )]
+        device: Option<&'static Device>,
+    },
+    BlankCheck {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_device,
+            help = "Target device (e.g., 16F628A)."
+        )]
+        device: &'static Device,
+    },
+    Erase {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        #[arg(
+            short,
+            long,
+            value_enum,
+            default_value_t = EraseTarget::Bulk,
+            help = "Memory to erase, bulk erase clears program memory, EEPROM and configuration."
+        )]
+        mode: EraseTarget,
+    },
+    Identify {
+        #[command(flatten)]
+        connection: ConnectionArgs,
     },
 }
@@ -0,0 +1,395 @@
+use crate::device::Device;
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::MemoryImage;
+use crate::info;
+
+/// A named group of bits in a configuration word. `address` is the hex file byte address of the
+/// 16-bit little-endian word holding the field and setting values are the masked bits packed together.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConfigField {
+    pub name: &'static str,
+    pub address: u32,
+    pub mask: u16,
+    pub settings: &'static [(&'static str, u16)],
+}
+
+impl ConfigField {
+    pub const fn new(
+        name: &'static str,
+        address: u32,
+        mask: u16,
+        settings: &'static [(&'static str, u16)],
+    ) -> ConfigField {
+        ConfigField {
+            name,
+            address,
+            mask,
+            settings,
+        }
+    }
+
+    pub fn value(&self, word: u16) -> u16 {
+        let mut value = 0;
+        let mut position = 0;
+
+        for bit in 0..16 {
+            if self.mask & (1 << bit) != 0 {
+                value |= ((word >> bit) & 1) << position;
+                position += 1;
+            }
+        }
+
+        value
+    }
+
+    /// Replaces the field bits of `word` with `value`, the inverse of [`ConfigField::value`].
+    pub fn with_value(&self, word: u16, value: u16) -> u16 {
+        let mut word = word & !self.mask;
+        let mut position = 0;
+
+        for bit in 0..16 {
+            if self.mask & (1 << bit) != 0 {
+                word |= ((value >> position) & 1) << bit;
+                position += 1;
+            }
+        }
+
+        word
+    }
+
+    pub fn setting(&self, word: u16) -> Option<&'static str> {
+        let value = self.value(word);
+
+        self.settings
+            .iter()
+            .find(|(_, setting_value)| *setting_value == value)
+            .map(|(name, _)| *name)
+    }
+}
+
+/// A `NAME=SETTING` pair given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    pub name: String,
+    pub setting: String,
+}
+
+pub fn parse_config_override(value: &str) -> Result<ConfigOverride, String> {
+    match value.split_once('=') {
+        Some((name, setting)) if !name.trim().is_empty() && !setting.trim().is_empty() => {
+            Ok(ConfigOverride {
+                name: name.trim().to_uppercase(),
+                setting: setting.trim().to_uppercase(),
+            })
+        }
+        _ => Err(String::from("expected NAME=SETTING (e.g., WDTE=OFF)")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchedConfigWord {
+    pub address: u32,
+    pub previous: Option<u16>,
+    pub word: u16,
+}
+
+/// Applies the overrides to the configuration words of the file, rewriting the records holding
+/// them or adding new records when the file does not contain the word yet.
+pub fn apply_overrides(
+    device: &Device,
+    hex_file: &mut HexFile,
+    overrides: &[ConfigOverride],
+) -> Result<Vec<PatchedConfigWord>, String> {
+    let image = MemoryImage::from_hex_file(hex_file);
+    let mut patched: Vec<PatchedConfigWord> = vec![];
+
+    for config_override in overrides {
+        let field = device
+            .config_fields
+            .iter()
+            .find(|field| field.name == config_override.name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = device.config_fields.iter().map(|f| f.name).collect();
+                format!(
+                    "unknown configuration field '{}' for {}, expected one of: {}",
+                    config_override.name,
+                    device.name,
+                    names.join(", ")
+                )
+            })?;
+
+        let value = field
+            .settings
+            .iter()
+            .find(|(name, _)| *name == config_override.setting)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| {
+                let mut names: Vec<&str> = field.settings.iter().map(|(name, _)| *name).collect();
+                names.dedup();
+                format!(
+                    "unknown setting '{}' for {}, expected one of: {}",
+                    config_override.setting,
+                    field.name,
+                    names.join(", ")
+                )
+            })?;
+
+        let index = match patched.iter().position(|w| w.address == field.address) {
+            Some(index) => index,
+            None => {
+                let previous = image.word(field.address);
+                patched.push(PatchedConfigWord {
+                    address: field.address,
+                    previous,
+                    word: previous.unwrap_or(device.erased_config_word(field.address)),
+                });
+                patched.len() - 1
+            }
+        };
+        patched[index].word = field.with_value(patched[index].word, value);
+    }
+
+    for word in &patched {
+        let bytes = word.word.to_le_bytes();
+        let missing: Vec<u32> = (0..2)
+            .filter(|offset| !hex_file.write_byte(word.address + offset, bytes[*offset as usize]))
+            .collect();
+
+        match missing.as_slice() {
+            [] => {}
+            [0, 1] => hex_file.insert_data(word.address, bytes.to_vec()),
+            _ => {
+                for offset in missing {
+                    hex_file.insert_data(word.address + offset, vec![bytes[offset as usize]]);
+                }
+            }
+        }
+    }
+
+    Ok(patched)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedField {
+    pub name: &'static str,
+    pub value: u16,
+    pub setting: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedConfigWord {
+    pub address: u32,
+    pub word: u16,
+    pub fields: Vec<DecodedField>,
+}
+
+/// Decodes every configuration word of the device present in the image.
+pub fn decode(device: &Device, image: &MemoryImage) -> Vec<DecodedConfigWord> {
+    let mut words: Vec<DecodedConfigWord> = vec![];
+
+    for field in device.config_fields {
+        let Some(word) = image.word(field.address) else {
+            continue;
+        };
+
+        let decoded_field = DecodedField {
+            name: field.name,
+            value: field.value(word),
+            setting: field.setting(word),
+        };
+
+        match words.iter_mut().find(|w| w.address == field.address) {
+            Some(decoded_word) => decoded_word.fields.push(decoded_field),
+            None => words.push(DecodedConfigWord {
+                address: field.address,
+                word,
+                fields: vec![decoded_field],
+            }),
+        }
+    }
+
+    words
+}
+
+pub fn print_config(device: &Device, image: &MemoryImage) {
+    let words = decode(device, image);
+    if words.is_empty() {
+        info!("no configuration words for {} found", device.name);
+        return;
+    }
+
+    for word in words {
+        info!("config 0x{:06X} = 0x{:04X}", word.address, word.word);
+
+        for field in word.fields {
+            match field.setting {
+                Some(setting) => info!("    {:<8} = {}", field.name, setting),
+                None => info!("    {:<8} = unknown (0x{:X})", field.name, field.value),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config_word::{
+        apply_overrides, decode, parse_config_override, DecodedField, PatchedConfigWord,
+    };
+    use crate::device::Device;
+    use crate::hex::hex_file::HexFile;
+    use crate::hex::memory_image::MemoryImage;
+
+    #[test]
+    fn given_mid_range_config_word_should_decode_named_settings() {
+        let device = Device::find("16F628A").unwrap();
+        let mut image = MemoryImage::default();
+        image.bytes.insert(0x400E, 0x14);
+        image.bytes.insert(0x400F, 0x3F);
+
+        let words = decode(device, &image);
+
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, 0x3F14);
+        let settings: Vec<(&str, Option<&str>)> = words[0]
+            .fields
+            .iter()
+            .map(|field| (field.name, field.setting))
+            .collect();
+        assert_eq!(
+            settings,
+            vec![
+                ("FOSC", Some("INTOSCIO")),
+                ("WDTE", Some("ON")),
+                ("PWRTE", Some("ON")),
+                ("MCLRE", Some("OFF")),
+                ("BOREN", Some("OFF")),
+                ("LVP", Some("OFF")),
+                ("CPD", Some("OFF")),
+                ("CP", Some("OFF")),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_value_without_setting_should_keep_raw_value() {
+        let device = Device::find("16F84A").unwrap();
+        let mut image = MemoryImage::default();
+        image.bytes.insert(0x400E, 0x1B);
+        image.bytes.insert(0x400F, 0x00);
+
+        let words = decode(device, &image);
+
+        assert_eq!(
+            words[0].fields[3],
+            DecodedField {
+                name: "CP",
+                value: 0x001,
+                setting: None,
+            }
+        );
+    }
+
+    #[test]
+    fn given_pic18_oscillator_with_ignored_low_bit_should_decode_setting() {
+        let device = Device::find("18F2550").unwrap();
+        let settings: Vec<Option<&str>> = [0x01, 0x03, 0x0D, 0x0F]
+            .into_iter()
+            .map(|fosc| {
+                let mut image = MemoryImage::default();
+                image.bytes.insert(0x300000, 0x00);
+                image.bytes.insert(0x300001, fosc);
+                decode(device, &image)[0].fields[3].setting
+            })
+            .collect();
+
+        assert_eq!(
+            settings,
+            vec![
+                Some("XT_XT"),
+                Some("XTPLL_XT"),
+                Some("HS"),
+                Some("HSPLL_HS")
+            ]
+        );
+    }
+
+    #[test]
+    fn given_image_without_config_words_should_not_decode_anything() {
+        let device = Device::find("16F84A").unwrap();
+
+        assert!(decode(device, &MemoryImage::default()).is_empty());
+    }
+
+    #[test]
+    fn given_overrides_should_patch_existing_config_record() {
+        let device = Device::find("16F628A").unwrap();
+        let mut file = HexFile::parse("test.hex", ":02400E00143F5D\n:00000001FF\n").unwrap();
+        let overrides = vec![
+            parse_config_override("wdte=off").unwrap(),
+            parse_config_override("MCLRE=ON").unwrap(),
+        ];
+
+        let patched = apply_overrides(device, &mut file, &overrides).unwrap();
+
+        assert_eq!(
+            patched,
+            vec![PatchedConfigWord {
+                address: 0x400E,
+                previous: Some(0x3F14),
+                word: 0x3F30,
+            }]
+        );
+        assert_eq!(file.lines[0].record.to_string(), ":02400E00303F41");
+    }
+
+    #[test]
+    fn given_file_without_config_word_should_add_it_from_erased_value() {
+        let device = Device::find("16F84A").unwrap();
+        let mut file = HexFile::parse("test.hex", ":00000001FF\n").unwrap();
+        let overrides = vec![parse_config_override("FOSC=XT").unwrap()];
+
+        apply_overrides(device, &mut file, &overrides).unwrap();
+
+        let image = MemoryImage::from_hex_file(&file);
+        assert_eq!(image.word(0x400E), Some(0x3FFD));
+    }
+
+    #[test]
+    fn given_pic18_file_without_config_should_keep_erased_values_of_other_fields() {
+        let device = Device::find("18F2550").unwrap();
+        let mut file = HexFile::parse("test.hex", ":00000001FF\n").unwrap();
+        let overrides = vec![
+            parse_config_override("WDTE=OFF").unwrap(),
+            parse_config_override("LVP=OFF").unwrap(),
+        ];
+
+        apply_overrides(device, &mut file, &overrides).unwrap();
+
+        let image = MemoryImage::from_hex_file(&file);
+        assert_eq!(image.word(0x300002), Some(0xFEDF));
+        assert_eq!(image.word(0x300006), Some(0xFFBB));
+        let settings: Vec<(&str, Option<&str>)> = decode(device, &image)
+            .iter()
+            .flat_map(|word| &word.fields)
+            .map(|field| (field.name, field.setting))
+            .collect();
+        assert!(settings.contains(&("VREGEN", Some("OFF"))));
+        assert!(settings.contains(&("WDTE", Some("OFF"))));
+        assert!(settings.contains(&("XINST", Some("OFF"))));
+        assert!(settings.contains(&("LVP", Some("OFF"))));
+        assert!(settings.contains(&("STVREN", Some("ON"))));
+    }
+
+    #[test]
+    fn given_unknown_field_or_setting_should_fail() {
+        let device = Device::find("16F84A").unwrap();
+        let mut file = HexFile::parse("test.hex", ":00000001FF\n").unwrap();
+
+        let unknown_field = vec![parse_config_override("LVP=OFF").unwrap()];
+        let unknown_setting = vec![parse_config_override("FOSC=INTOSCIO").unwrap()];
+
+        assert!(apply_overrides(device, &mut file, &unknown_field).is_err());
+        assert!(apply_overrides(device, &mut file, &unknown_setting).is_err());
+        assert!(parse_config_override("WDTE").is_err());
+    }
+}
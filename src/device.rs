@@ -0,0 +1,536 @@
+use crate::config_word::ConfigField;
+use std::fmt;
+use std::ops::Range;
+
+const DEVICE_ID_MASK: u16 = 0xFFE0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    Pic10,
+    Pic12,
+    Pic16,
+    Pic18,
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Family::Pic10 => "PIC10",
+            Family::Pic12 => "PIC12",
+            Family::Pic16 => "PIC16",
+            Family::Pic18 => "PIC18",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionKind {
+    Program,
+    Eeprom,
+    UserId,
+    Config,
+    DeviceId,
+}
+
+impl fmt::Display for RegionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RegionKind::Program => "program memory",
+            RegionKind::Eeprom => "EEPROM",
+            RegionKind::UserId => "user ID",
+            RegionKind::Config => "configuration",
+            RegionKind::DeviceId => "device ID",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A memory region expressed in hex file byte addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub kind: RegionKind,
+    pub range: Range<u32>,
+}
+
+/// Memory map of a device. Every address is a hex file byte address, so word addressed parts
+/// (PIC10/12/16) use twice their datasheet word address and store one EEPROM byte per word.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Device {
+    pub name: &'static str,
+    pub family: Family,
+    pub word_size: u32,
+    pub program_words: u32,
+    pub eeprom_size: u32,
+    pub eeprom_address: u32,
+    pub config: Range<u32>,
+    pub user_id: Range<u32>,
+    pub device_id_address: Option<u32>,
+    pub device_id: Option<u16>,
+    pub config_fields: &'static [ConfigField],
+    pub erased_config: &'static [ErasedConfig],
+}
+
+/// Erased state of a configuration word. Only the bits in `mask` are implemented, the others read
+/// back as whatever the part returns for unimplemented or factory calibrated bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErasedConfig {
+    pub address: u32,
+    pub value: u16,
+    pub mask: u16,
+}
+
+impl ErasedConfig {
+    pub const fn new(address: u32, value: u16, mask: u16) -> ErasedConfig {
+        ErasedConfig {
+            address,
+            value,
+            mask,
+        }
+    }
+
+    pub fn is_erased(&self, word: u16) -> bool {
+        word & self.mask == self.value & self.mask
+    }
+}
+
+pub const DEVICES: &[Device] = &[
+    Device {
+        name: "PIC10F200",
+        family: Family::Pic10,
+        word_size: 12,
+        program_words: 256,
+        eeprom_size: 0,
+        eeprom_address: 0,
+        config: 0x1FFE..0x2000,
+        user_id: 0x0200..0x0208,
+        device_id_address: None,
+        device_id: None,
+        config_fields: PIC10F200_CONFIG,
+        erased_config: PIC10F200_ERASED_CONFIG,
+    },
+    Device {
+        name: "PIC12F675",
+        family: Family::Pic12,
+        word_size: 14,
+        program_words: 1024,
+        eeprom_size: 128,
+        eeprom_address: 0x4200,
+        config: 0x400E..0x4010,
+        user_id: 0x4000..0x4008,
+        device_id_address: Some(0x400C),
+        device_id: Some(0x0FC0),
+        config_fields: PIC12F675_CONFIG,
+        erased_config: PIC12F675_ERASED_CONFIG,
+    },
+    Device {
+        name: "PIC16F84A",
+        family: Family::Pic16,
+        word_size: 14,
+        program_words: 1024,
+        eeprom_size: 64,
+        eeprom_address: 0x4200,
+        config: 0x400E..0x4010,
+        user_id: 0x4000..0x4008,
+        device_id_address: Some(0x400C),
+        device_id: Some(0x0560),
+        config_fields: PIC16F84A_CONFIG,
+        erased_config: PIC16F84A_ERASED_CONFIG,
+    },
+    Device {
+        name: "PIC16F628A",
+        family: Family::Pic16,
+        word_size: 14,
+        program_words: 2048,
+        eeprom_size: 128,
+        eeprom_address: 0x4200,
+        config: 0x400E..0x4010,
+        user_id: 0x4000..0x4008,
+        device_id_address: Some(0x400C),
+        device_id: Some(0x1060),
+        config_fields: PIC16F628A_CONFIG,
+        erased_config: PIC16F628A_ERASED_CONFIG,
+    },
+    Device {
+        name: "PIC16F877A",
+        family: Family::Pic16,
+        word_size: 14,
+        program_words: 8192,
+        eeprom_size: 256,
+        eeprom_address: 0x4200,
+        config: 0x400E..0x4010,
+        user_id: 0x4000..0x4008,
+        device_id_address: Some(0x400C),
+        device_id: Some(0x0E20),
+        config_fields: PIC16F877A_CONFIG,
+        erased_config: PIC16F877A_ERASED_CONFIG,
+    },
+    Device {
+        name: "PIC18F2550",
+        family: Family::Pic18,
+        word_size: 16,
+        program_words: 16384,
+        eeprom_size: 256,
+        eeprom_address: 0xF00000,
+        config: 0x300000..0x30000E,
+        user_id: 0x200000..0x200008,
+        device_id_address: Some(0x3FFFFE),
+        device_id: Some(0x1240),
+        config_fields: PIC18F2550_CONFIG,
+        erased_config: PIC18F2550_ERASED_CONFIG,
+    },
+];
+
+const OFF_ON: &[(&str, u16)] = &[("OFF", 0), ("ON", 1)];
+const ON_OFF: &[(&str, u16)] = &[("ON", 0), ("OFF", 1)];
+
+const PIC10F200_CONFIG: &[ConfigField] = &[
+    ConfigField::new("WDTE", 0x1FFE, 0x0004, OFF_ON),
+    ConfigField::new("CP", 0x1FFE, 0x0008, ON_OFF),
+    ConfigField::new("MCLRE", 0x1FFE, 0x0010, OFF_ON),
+];
+
+const PIC10F200_ERASED_CONFIG: &[ErasedConfig] = &[ErasedConfig::new(0x1FFE, 0x001C, 0x001C)];
+
+const PIC12F675_CONFIG: &[ConfigField] = &[
+    ConfigField::new(
+        "FOSC",
+        0x400E,
+        0x0007,
+        &[
+            ("LP", 0),
+            ("XT", 1),
+            ("HS", 2),
+            ("EC", 3),
+            ("INTRCIO", 4),
+            ("INTRCCLK", 5),
+            ("EXTRCIO", 6),
+            ("EXTRCCLK", 7),
+        ],
+    ),
+    ConfigField::new("WDTE", 0x400E, 0x0008, OFF_ON),
+    ConfigField::new("PWRTE", 0x400E, 0x0010, ON_OFF),
+    ConfigField::new("MCLRE", 0x400E, 0x0020, OFF_ON),
+    ConfigField::new("BOREN", 0x400E, 0x0040, OFF_ON),
+    ConfigField::new("CP", 0x400E, 0x0080, ON_OFF),
+    ConfigField::new("CPD", 0x400E, 0x0100, ON_OFF),
+];
+
+// The bandgap calibration bits 13:12 survive a bulk erase.
+const PIC12F675_ERASED_CONFIG: &[ErasedConfig] = &[ErasedConfig::new(0x400E, 0x01FF, 0x01FF)];
+
+const PIC16F84A_CONFIG: &[ConfigField] = &[
+    ConfigField::new(
+        "FOSC",
+        0x400E,
+        0x0003,
+        &[("LP", 0), ("XT", 1), ("HS", 2), ("RC", 3)],
+    ),
+    ConfigField::new("WDTE", 0x400E, 0x0004, OFF_ON),
+    ConfigField::new("PWRTE", 0x400E, 0x0008, ON_OFF),
+    ConfigField::new("CP", 0x400E, 0x3FF0, &[("ON", 0), ("OFF", 0x03FF)]),
+];
+
+const PIC16F84A_ERASED_CONFIG: &[ErasedConfig] = &[ErasedConfig::new(0x400E, 0x3FFF, 0x3FFF)];
+
+const PIC16F628A_CONFIG: &[ConfigField] = &[
+    ConfigField::new(
+        "FOSC",
+        0x400E,
+        0x0013,
+        &[
+            ("LP", 0x00),
+            ("XT", 0x01),
+            ("HS", 0x02),
+            ("EXTCLK", 0x03),
+            ("INTOSCIO", 0x04),
+            ("INTOSCCLK", 0x05),
+            ("EXTRCIO", 0x06),
+            ("EXTRCCLK", 0x07),
+        ],
+    ),
+    ConfigField::new("WDTE", 0x400E, 0x0004, OFF_ON),
+    ConfigField::new("PWRTE", 0x400E, 0x0008, ON_OFF),
+    ConfigField::new("MCLRE", 0x400E, 0x0020, OFF_ON),
+    ConfigField::new("BOREN", 0x400E, 0x0040, OFF_ON),
+    ConfigField::new("LVP", 0x400E, 0x0080, OFF_ON),
+    ConfigField::new("CPD", 0x400E, 0x0100, ON_OFF),
+    ConfigField::new("CP", 0x400E, 0x2000, ON_OFF),
+];
+
+const PIC16F628A_ERASED_CONFIG: &[ErasedConfig] = &[ErasedConfig::new(0x400E, 0x21FF, 0x21FF)];
+
+const PIC16F877A_CONFIG: &[ConfigField] = &[
+    ConfigField::new(
+        "FOSC",
+        0x400E,
+        0x0003,
+        &[("LP", 0), ("XT", 1), ("HS", 2), ("RC", 3)],
+    ),
+    ConfigField::new("WDTE", 0x400E, 0x0004, OFF_ON),
+    ConfigField::new("PWRTE", 0x400E, 0x0008, ON_OFF),
+    ConfigField::new("BOREN", 0x400E, 0x0040, OFF_ON),
+    ConfigField::new("LVP", 0x400E, 0x0080, OFF_ON),
+    ConfigField::new("CPD", 0x400E, 0x0100, ON_OFF),
+    ConfigField::new(
+        "WRT",
+        0x400E,
+        0x0600,
+        &[("HALF", 0), ("1FOURTH", 1), ("256", 2), ("OFF", 3)],
+    ),
+    ConfigField::new("DEBUG", 0x400E, 0x0800, ON_OFF),
+    ConfigField::new("CP", 0x400E, 0x2000, ON_OFF),
+];
+
+const PIC16F877A_ERASED_CONFIG: &[ErasedConfig] = &[ErasedConfig::new(0x400E, 0x2FCF, 0x2FCF)];
+
+const PIC18F2550_CONFIG: &[ConfigField] = &[
+    ConfigField::new(
+        "PLLDIV",
+        0x300000,
+        0x0007,
+        &[
+            ("1", 0),
+            ("2", 1),
+            ("3", 2),
+            ("4", 3),
+            ("5", 4),
+            ("6", 5),
+            ("10", 6),
+            ("12", 7),
+        ],
+    ),
+    ConfigField::new(
+        "CPUDIV",
+        0x300000,
+        0x0018,
+        &[
+            ("OSC1_PLL2", 0),
+            ("OSC2_PLL3", 1),
+            ("OSC3_PLL4", 2),
+            ("OSC4_PLL6", 3),
+        ],
+    ),
+    ConfigField::new("USBDIV", 0x300000, 0x0020, &[("1", 0), ("2", 1)]),
+    ConfigField::new(
+        "FOSC",
+        0x300000,
+        0x0F00,
+        &[
+            ("XT_XT", 0x0),
+            ("XT_XT", 0x1),
+            ("XTPLL_XT", 0x2),
+            ("XTPLL_XT", 0x3),
+            ("ECIO_EC", 0x4),
+            ("EC_EC", 0x5),
+            ("ECPIO_EC", 0x6),
+            ("ECPLL_EC", 0x7),
+            ("INTOSCIO_EC", 0x8),
+            ("INTOSC_EC", 0x9),
+            ("INTOSC_XT", 0xA),
+            ("INTOSC_HS", 0xB),
+            ("HS", 0xC),
+            ("HS", 0xD),
+            ("HSPLL_HS", 0xE),
+            ("HSPLL_HS", 0xF),
+        ],
+    ),
+    ConfigField::new("FCMEN", 0x300000, 0x4000, OFF_ON),
+    ConfigField::new("IESO", 0x300000, 0x8000, OFF_ON),
+    ConfigField::new("PWRTE", 0x300002, 0x0001, ON_OFF),
+    ConfigField::new(
+        "BOREN",
+        0x300002,
+        0x0006,
+        &[("OFF", 0), ("SOFT", 1), ("ON_ACTIVE", 2), ("ON", 3)],
+    ),
+    ConfigField::new("VREGEN", 0x300002, 0x0020, OFF_ON),
+    ConfigField::new("WDTE", 0x300002, 0x0100, OFF_ON),
+    ConfigField::new("PBADEN", 0x300004, 0x0200, OFF_ON),
+    ConfigField::new("MCLRE", 0x300004, 0x8000, OFF_ON),
+    ConfigField::new("STVREN", 0x300006, 0x0001, OFF_ON),
+    ConfigField::new("LVP", 0x300006, 0x0004, OFF_ON),
+    ConfigField::new("XINST", 0x300006, 0x0040, OFF_ON),
+    ConfigField::new("DEBUG", 0x300006, 0x0080, ON_OFF),
+    ConfigField::new("CP0", 0x300008, 0x0001, ON_OFF),
+    ConfigField::new("CP1", 0x300008, 0x0002, ON_OFF),
+    ConfigField::new("CP2", 0x300008, 0x0004, ON_OFF),
+    ConfigField::new("CP3", 0x300008, 0x0008, ON_OFF),
+    ConfigField::new("CPD", 0x300008, 0x8000, ON_OFF),
+];
+
+// CONFIG1L to CONFIG7H as listed in the datasheet, low byte first: several fields, e.g. PLLDIV,
+// FOSC or XINST, do not erase to all ones.
+const PIC18F2550_ERASED_CONFIG: &[ErasedConfig] = &[
+    ErasedConfig::new(0x300000, 0x0500, 0xCF3F),
+    ErasedConfig::new(0x300002, 0x1F1F, 0x1F3F),
+    ErasedConfig::new(0x300004, 0x8300, 0x8700),
+    ErasedConfig::new(0x300006, 0x0085, 0x00C5),
+    ErasedConfig::new(0x300008, 0xC00F, 0xC00F),
+    ErasedConfig::new(0x30000A, 0xE00F, 0xE00F),
+    ErasedConfig::new(0x30000C, 0x400F, 0x400F),
+];
+
+impl Device {
+    /// Looks a device up by name, ignoring case and an optional `PIC` prefix.
+    pub fn find(name: &str) -> Option<&'static Device> {
+        let name = name.to_uppercase();
+        let name = name.strip_prefix("PIC").unwrap_or(&name);
+
+        DEVICES
+            .iter()
+            .find(|device| device.name.strip_prefix("PIC") == Some(name))
+    }
+
+    /// Size in hex file bytes of one EEPROM byte.
+    fn eeprom_stride(&self) -> u32 {
+        match self.family {
+            Family::Pic18 => 1,
+            _ => 2,
+        }
+    }
+
+    pub fn regions(&self) -> Vec<MemoryRegion> {
+        let mut regions = vec![MemoryRegion {
+            kind: RegionKind::Program,
+            range: 0..self.program_words * 2,
+        }];
+
+        if self.eeprom_size > 0 {
+            regions.push(MemoryRegion {
+                kind: RegionKind::Eeprom,
+                range: self.eeprom_address
+                    ..self.eeprom_address + self.eeprom_size * self.eeprom_stride(),
+            });
+        }
+
+        regions.push(MemoryRegion {
+            kind: RegionKind::UserId,
+            range: self.user_id.clone(),
+        });
+        regions.push(MemoryRegion {
+            kind: RegionKind::Config,
+            range: self.config.clone(),
+        });
+
+        if let Some(address) = self.device_id_address {
+            regions.push(MemoryRegion {
+                kind: RegionKind::DeviceId,
+                range: address..address + 2,
+            });
+        }
+
+        regions
+    }
+
+    pub fn erased_word(&self) -> u16 {
+        ((1u32 << self.word_size) - 1) as u16
+    }
+
+    /// Erased value of the configuration word at `address`, with its unimplemented bits set.
+    pub fn erased_config_word(&self, address: u32) -> u16 {
+        let erased_word = self.erased_word();
+        self.erased_config
+            .iter()
+            .find(|erased| erased.address == address)
+            .map_or(erased_word, |erased| {
+                (erased_word & !erased.mask) | (erased.value & erased.mask)
+            })
+    }
+
+    /// Ranges a hex file is allowed to write, the device ID is read-only.
+    pub fn writable_ranges(&self) -> Vec<Range<u32>> {
+        self.regions()
+            .into_iter()
+            .filter(|region| region.kind != RegionKind::DeviceId)
+            .map(|region| region.range)
+            .collect()
+    }
+
+    pub fn region_of(&self, address: u32) -> Option<RegionKind> {
+        self.regions()
+            .into_iter()
+            .find(|region| region.range.contains(&address))
+            .map(|region| region.kind)
+    }
+
+    /// Whether a device ID read from a chip belongs to this device, ignoring the revision bits.
+    pub fn matches_device_id(&self, device_id: u16) -> bool {
+        self.device_id
+            .is_some_and(|expected| expected & DEVICE_ID_MASK == device_id & DEVICE_ID_MASK)
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}-bit words, {} program words, {} EEPROM bytes)",
+            self.name, self.word_size, self.program_words, self.eeprom_size
+        )
+    }
+}
+
+pub fn parse_device(name: &str) -> Result<&'static Device, String> {
+    Device::find(name).ok_or_else(|| {
+        let names: Vec<&str> = DEVICES.iter().map(|device| device.name).collect();
+        format!("unknown device, supported devices: {}", names.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::device::{Device, RegionKind, DEVICES};
+
+    #[test]
+    fn given_name_without_prefix_or_in_lowercase_should_find_device() {
+        assert_eq!(Device::find("16f628a").unwrap().name, "PIC16F628A");
+        assert_eq!(Device::find("PIC18F2550").unwrap().name, "PIC18F2550");
+        assert!(Device::find("16F999").is_none());
+    }
+
+    #[test]
+    fn given_mid_range_device_should_map_word_addresses_to_hex_addresses() {
+        let device = Device::find("16F84A").unwrap();
+
+        assert_eq!(device.region_of(0x07FE), Some(RegionKind::Program));
+        assert_eq!(device.region_of(0x0800), None);
+        assert_eq!(device.region_of(0x427E), Some(RegionKind::Eeprom));
+        assert_eq!(device.region_of(0x4280), None);
+        assert_eq!(device.region_of(0x400E), Some(RegionKind::Config));
+    }
+
+    #[test]
+    fn given_device_id_with_revision_should_match_device() {
+        let device = Device::find("16F628A").unwrap();
+
+        assert!(device.matches_device_id(0x1066));
+        assert!(!device.matches_device_id(0x0566));
+    }
+
+    #[test]
+    fn given_config_fields_should_be_covered_by_erased_config() {
+        for device in DEVICES {
+            for field in device.config_fields {
+                let erased = device
+                    .erased_config
+                    .iter()
+                    .find(|erased| erased.address == field.address)
+                    .unwrap();
+
+                assert_eq!(
+                    field.mask & !erased.mask,
+                    0,
+                    "{} {}",
+                    device.name,
+                    field.name
+                );
+            }
+            for erased in device.erased_config {
+                assert!(device.config.contains(&erased.address), "{}", device.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+pub mod pic14;
+pub mod pic18;
+
+use crate::hex::memory_image::MemoryImage;
+use std::ops::Range;
+
+/// One decoded instruction, `address` is the hex file byte address of its first word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u32,
+    pub words: Vec<u16>,
+    pub text: String,
+}
+
+/// Disassembles the mid-range program words of the image that fall inside `program`.
+pub fn disassemble_pic14(image: &MemoryImage, program: Range<u32>) -> Vec<Instruction> {
+    let mut addresses: Vec<u32> = image
+        .bytes
+        .range(program)
+        .map(|(address, _)| address & !1)
+        .collect();
+    addresses.dedup();
+
+    addresses
+        .into_iter()
+        .filter_map(|address| {
+            let word = image.word(address)?;
+            Some(Instruction {
+                address,
+                words: vec![word],
+                text: pic14::decode(word),
+            })
+        })
+        .collect()
+}
+
+/// Disassembles the PIC18 program memory of the image inside `program`, following two-word instructions.
+pub fn disassemble_pic18(image: &MemoryImage, program: Range<u32>) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    let mut addresses: Vec<u32> = image
+        .bytes
+        .range(program)
+        .map(|(address, _)| address & !1)
+        .collect();
+    addresses.dedup();
+
+    let mut index = 0;
+    while index < addresses.len() {
+        let address = addresses[index];
+        let Some(word) = image.word(address) else {
+            index += 1;
+            continue;
+        };
+
+        let next = image.word(address + 2);
+        let (text, length) = pic18::decode(address, word, next);
+        let mut words = vec![word];
+        if length == 2 {
+            words.extend(next);
+            if addresses.get(index + 1) == Some(&(address + 2)) {
+                index += 1;
+            }
+        }
+
+        instructions.push(Instruction {
+            address,
+            words,
+            text,
+        });
+        index += 1;
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod test {
+    use crate::disasm::disassemble_pic18;
+    use crate::hex::hex_file::HexFile;
+    use crate::hex::memory_image::MemoryImage;
+
+    #[test]
+    fn given_two_word_instruction_should_not_disassemble_its_second_word() {
+        let file = HexFile::parse("test.hex", ":06000000550E10EF00F0A8\n").unwrap();
+
+        let instructions = disassemble_pic18(&MemoryImage::from_hex_file(&file), 0..0x8000);
+
+        let texts: Vec<&str> = instructions.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["MOVLW 0x55", "GOTO 0x000020"]);
+        assert_eq!(instructions[1].words, vec![0xEF10, 0xF000]);
+    }
+}
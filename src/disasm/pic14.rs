@@ -0,0 +1,129 @@
+/// Decodes one 14-bit mid-range instruction word into its mnemonic and operands.
+pub fn decode(word: u16) -> String {
+    let word = word & 0x3FFF;
+    let file = word & 0x007F;
+    let destination = if word & 0x0080 != 0 { "F" } else { "W" };
+    let bit = (word >> 7) & 0x0007;
+    let literal = word & 0x00FF;
+    let address = word & 0x07FF;
+
+    match word >> 12 {
+        0b00 => decode_byte_oriented(word, file, destination),
+        0b01 => {
+            let mnemonic = match (word >> 10) & 0b11 {
+                0b00 => "BCF",
+                0b01 => "BSF",
+                0b10 => "BTFSC",
+                _ => "BTFSS",
+            };
+            format!("{} 0x{:02X}, {}", mnemonic, file, bit)
+        }
+        0b10 => {
+            let mnemonic = if word & 0x0800 == 0 { "CALL" } else { "GOTO" };
+            format!("{} 0x{:03X}", mnemonic, address)
+        }
+        _ => {
+            let mnemonic = match (word >> 8) & 0x0F {
+                0x0..=0x3 => "MOVLW",
+                0x4..=0x7 => "RETLW",
+                0x8 => "IORLW",
+                0x9 => "ANDLW",
+                0xA => "XORLW",
+                0xB => return unknown(word),
+                0xC | 0xD => "SUBLW",
+                _ => "ADDLW",
+            };
+            format!("{} 0x{:02X}", mnemonic, literal)
+        }
+    }
+}
+
+fn decode_byte_oriented(word: u16, file: u16, destination: &str) -> String {
+    let mnemonic = match (word >> 8) & 0x0F {
+        0x0 => return decode_control(word, file),
+        0x1 => {
+            return if word & 0x0080 != 0 {
+                format!("CLRF 0x{:02X}", file)
+            } else {
+                String::from("CLRW")
+            };
+        }
+        0x2 => "SUBWF",
+        0x3 => "DECF",
+        0x4 => "IORWF",
+        0x5 => "ANDWF",
+        0x6 => "XORWF",
+        0x7 => "ADDWF",
+        0x8 => "MOVF",
+        0x9 => "COMF",
+        0xA => "INCF",
+        0xB => "DECFSZ",
+        0xC => "RRF",
+        0xD => "RLF",
+        0xE => "SWAPF",
+        _ => "INCFSZ",
+    };
+
+    format!("{} 0x{:02X}, {}", mnemonic, file, destination)
+}
+
+fn decode_control(word: u16, file: u16) -> String {
+    match word {
+        0x0008 => String::from("RETURN"),
+        0x0009 => String::from("RETFIE"),
+        0x0062 => String::from("OPTION"),
+        0x0063 => String::from("SLEEP"),
+        0x0064 => String::from("CLRWDT"),
+        0x0065..=0x0067 => format!("TRIS 0x{:02X}", file),
+        _ if word & 0x0080 != 0 => format!("MOVWF 0x{:02X}", file),
+        _ if word & 0x009F == 0 => String::from("NOP"),
+        _ => unknown(word),
+    }
+}
+
+fn unknown(word: u16) -> String {
+    format!("DW 0x{:04X}", word)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::disasm::pic14::decode;
+
+    #[test]
+    fn given_instruction_words_should_decode_mnemonics_and_operands() {
+        let instructions = [
+            (0x3055, "MOVLW 0x55"),
+            (0x1683, "BSF 0x03, 5"),
+            (0x1283, "BCF 0x03, 5"),
+            (0x1C03, "BTFSS 0x03, 0"),
+            (0x2805, "GOTO 0x005"),
+            (0x2123, "CALL 0x123"),
+            (0x0085, "MOVWF 0x05"),
+            (0x0820, "MOVF 0x20, W"),
+            (0x0AA0, "INCF 0x20, F"),
+            (0x0BA1, "DECFSZ 0x21, F"),
+            (0x01A0, "CLRF 0x20"),
+            (0x0103, "CLRW"),
+            (0x0000, "NOP"),
+            (0x0008, "RETURN"),
+            (0x0009, "RETFIE"),
+            (0x0064, "CLRWDT"),
+            (0x0063, "SLEEP"),
+            (0x3400, "RETLW 0x00"),
+            (0x3E01, "ADDLW 0x01"),
+            (0x3C0A, "SUBLW 0x0A"),
+            (0x39F0, "ANDLW 0xF0"),
+            (0x3A0F, "XORLW 0x0F"),
+        ];
+
+        for (word, text) in instructions {
+            assert_eq!(decode(word), text, "word 0x{:04X}", word);
+        }
+    }
+
+    #[test]
+    fn given_unused_opcode_should_print_raw_word() {
+        assert_eq!(decode(0x3B00), "DW 0x3B00");
+        assert_eq!(decode(0x0001), "DW 0x0001");
+    }
+}
@@ -0,0 +1,269 @@
+/// Decodes the PIC18 instruction starting with `word`, located at byte address `address`.
+/// `next` is the following program word, used by two-word instructions.
+/// Returns the text and the number of words the instruction takes.
+pub fn decode(address: u32, word: u16, next: Option<u16>) -> (String, usize) {
+    match word >> 8 {
+        0xC0..=0xCF => match second_word(next) {
+            Some(operand) => (
+                format!("MOVFF 0x{:03X}, 0x{:03X}", word & 0x0FFF, operand),
+                2,
+            ),
+            None => (unknown(word), 1),
+        },
+        0xEC | 0xED | 0xEF => match second_word(next) {
+            Some(operand) => {
+                let target = (((operand as u32) << 8) | (word as u32 & 0x00FF)) << 1;
+                let text = if word >> 8 == 0xEF {
+                    format!("GOTO 0x{:06X}", target)
+                } else {
+                    format!("CALL 0x{:06X}, {}", target, (word >> 8) & 1)
+                };
+                (text, 2)
+            }
+            None => (unknown(word), 1),
+        },
+        0xEE if word & 0x00C0 == 0 => match second_word(next) {
+            Some(operand) if operand & 0x0F00 == 0 => (
+                format!(
+                    "LFSR {}, 0x{:03X}",
+                    (word >> 4) & 0x3,
+                    ((word & 0x000F) << 8) | operand
+                ),
+                2,
+            ),
+            _ => (unknown(word), 1),
+        },
+        _ => (decode_single(address, word), 1),
+    }
+}
+
+fn decode_single(address: u32, word: u16) -> String {
+    let high = word >> 8;
+    let literal = word & 0x00FF;
+
+    match high {
+        0x00 => decode_control(word),
+        0x01 if word & 0x00F0 == 0 => format!("MOVLB 0x{:X}", word & 0x000F),
+        0x02..=0x03 => format!("MULWF {}", file_access(word)),
+        0x04..=0x07 => format!("DECF {}", file_destination_access(word)),
+        0x08..=0x0F => {
+            let mnemonic = match high {
+                0x08 => "SUBLW",
+                0x09 => "IORLW",
+                0x0A => "XORLW",
+                0x0B => "ANDLW",
+                0x0C => "RETLW",
+                0x0D => "MULLW",
+                0x0E => "MOVLW",
+                _ => "ADDLW",
+            };
+            format!("{} 0x{:02X}", mnemonic, literal)
+        }
+        0x10..=0x5F => {
+            let mnemonic = match word >> 10 {
+                0b000100 => "IORWF",
+                0b000101 => "ANDWF",
+                0b000110 => "XORWF",
+                0b000111 => "COMF",
+                0b001000 => "ADDWFC",
+                0b001001 => "ADDWF",
+                0b001010 => "INCF",
+                0b001011 => "DECFSZ",
+                0b001100 => "RRCF",
+                0b001101 => "RLCF",
+                0b001110 => "SWAPF",
+                0b001111 => "INCFSZ",
+                0b010000 => "RRNCF",
+                0b010001 => "RLNCF",
+                0b010010 => "INFSNZ",
+                0b010011 => "DCFSNZ",
+                0b010100 => "MOVF",
+                0b010101 => "SUBFWB",
+                0b010110 => "SUBWFB",
+                _ => "SUBWF",
+            };
+            format!("{} {}", mnemonic, file_destination_access(word))
+        }
+        0x60..=0x6F => {
+            let mnemonic = match word >> 9 {
+                0b0110000 => "CPFSLT",
+                0b0110001 => "CPFSEQ",
+                0b0110010 => "CPFSGT",
+                0b0110011 => "TSTFSZ",
+                0b0110100 => "SETF",
+                0b0110101 => "CLRF",
+                0b0110110 => "NEGF",
+                _ => "MOVWF",
+            };
+            format!("{} {}", mnemonic, file_access(word))
+        }
+        0x70..=0xBF => {
+            let mnemonic = match word >> 12 {
+                0x7 => "BTG",
+                0x8 => "BSF",
+                0x9 => "BCF",
+                0xA => "BTFSS",
+                _ => "BTFSC",
+            };
+            format!(
+                "{} 0x{:02X}, {}, {}",
+                mnemonic,
+                literal,
+                (word >> 9) & 0x7,
+                access(word)
+            )
+        }
+        0xD0..=0xDF => {
+            let mnemonic = if word & 0x0800 == 0 { "BRA" } else { "RCALL" };
+            let offset = sign_extend(word & 0x07FF, 11);
+            format!("{} 0x{:06X}", mnemonic, branch_target(address, offset))
+        }
+        0xE0..=0xE7 => {
+            let mnemonic = match high {
+                0xE0 => "BZ",
+                0xE1 => "BNZ",
+                0xE2 => "BC",
+                0xE3 => "BNC",
+                0xE4 => "BOV",
+                0xE5 => "BNOV",
+                0xE6 => "BN",
+                _ => "BNN",
+            };
+            let offset = sign_extend(literal, 8);
+            format!("{} 0x{:06X}", mnemonic, branch_target(address, offset))
+        }
+        0xF0..=0xFF => String::from("NOP"),
+        _ => unknown(word),
+    }
+}
+
+fn decode_control(word: u16) -> String {
+    let text = match word & 0x00FF {
+        0x00 => "NOP",
+        0x03 => "SLEEP",
+        0x04 => "CLRWDT",
+        0x05 => "PUSH",
+        0x06 => "POP",
+        0x07 => "DAW",
+        0x08 => "TBLRD*",
+        0x09 => "TBLRD*+",
+        0x0A => "TBLRD*-",
+        0x0B => "TBLRD+*",
+        0x0C => "TBLWT*",
+        0x0D => "TBLWT*+",
+        0x0E => "TBLWT*-",
+        0x0F => "TBLWT+*",
+        0x10 | 0x11 => return format!("RETFIE {}", word & 1),
+        0x12 | 0x13 => return format!("RETURN {}", word & 1),
+        0xFF => "RESET",
+        _ => return unknown(word),
+    };
+
+    String::from(text)
+}
+
+fn second_word(next: Option<u16>) -> Option<u16> {
+    next.filter(|word| word >> 12 == 0xF)
+        .map(|word| word & 0x0FFF)
+}
+
+fn access(word: u16) -> &'static str {
+    if word & 0x0100 != 0 {
+        "BANKED"
+    } else {
+        "ACCESS"
+    }
+}
+
+fn file_access(word: u16) -> String {
+    format!("0x{:02X}, {}", word & 0x00FF, access(word))
+}
+
+fn file_destination_access(word: u16) -> String {
+    let destination = if word & 0x0200 != 0 { "F" } else { "W" };
+    format!("0x{:02X}, {}, {}", word & 0x00FF, destination, access(word))
+}
+
+fn sign_extend(value: u16, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((value as i32) << shift) >> shift
+}
+
+fn branch_target(address: u32, offset: i32) -> u32 {
+    (address as i64 + 2 + 2 * offset as i64) as u32
+}
+
+fn unknown(word: u16) -> String {
+    format!("DW 0x{:04X}", word)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::disasm::pic18::decode;
+
+    #[test]
+    fn given_single_word_instructions_should_decode_them() {
+        let instructions = [
+            (0x0E55, "MOVLW 0x55"),
+            (0x6E81, "MOVWF 0x81, ACCESS"),
+            (0x2620, "ADDWF 0x20, F, ACCESS"),
+            (0x5121, "MOVF 0x21, W, BANKED"),
+            (0x6A20, "CLRF 0x20, ACCESS"),
+            (0x8E8B, "BSF 0x8B, 7, ACCESS"),
+            (0x9C8B, "BCF 0x8B, 6, ACCESS"),
+            (0xA4D8, "BTFSS 0xD8, 2, ACCESS"),
+            (0x0102, "MOVLB 0x2"),
+            (0x0012, "RETURN 0"),
+            (0x0011, "RETFIE 1"),
+            (0x0004, "CLRWDT"),
+            (0x0009, "TBLRD*+"),
+            (0x0000, "NOP"),
+            (0x00FF, "RESET"),
+        ];
+
+        for (word, text) in instructions {
+            assert_eq!(
+                decode(0, word, None),
+                (String::from(text), 1),
+                "word 0x{:04X}",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn given_relative_branches_should_print_absolute_targets() {
+        assert_eq!(decode(0x0100, 0xD7FF, None).0, "BRA 0x000100");
+        assert_eq!(decode(0x0100, 0xD802, None).0, "RCALL 0x000106");
+        assert_eq!(decode(0x0100, 0xE1FE, None).0, "BNZ 0x0000FE");
+    }
+
+    #[test]
+    fn given_two_word_instructions_should_consume_second_word() {
+        assert_eq!(
+            decode(0, 0xEF10, Some(0xF000)),
+            (String::from("GOTO 0x000020"), 2)
+        );
+        assert_eq!(
+            decode(0, 0xEC80, Some(0xF001)),
+            (String::from("CALL 0x000300, 0"), 2)
+        );
+        assert_eq!(
+            decode(0, 0xC020, Some(0xF081)),
+            (String::from("MOVFF 0x020, 0x081"), 2)
+        );
+        assert_eq!(
+            decode(0, 0xEE01, Some(0xF000)),
+            (String::from("LFSR 0, 0x100"), 2)
+        );
+    }
+
+    #[test]
+    fn given_two_word_instruction_without_second_word_should_print_raw_word() {
+        assert_eq!(decode(0, 0xEF10, None), (String::from("DW 0xEF10"), 1));
+        assert_eq!(
+            decode(0, 0xEF10, Some(0x0E55)),
+            (String::from("DW 0xEF10"), 1)
+        );
+    }
+}
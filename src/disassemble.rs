@@ -0,0 +1,54 @@
+use crate::device::{Device, Family};
+use crate::disasm::{disassemble_pic14, disassemble_pic18, Instruction};
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::MemoryImage;
+use crate::info;
+use crate::programmer::error::ProgrammerError;
+
+/// Program memory of mid-range parts ends where user IDs and configuration start (word 0x2000).
+const PIC14_PROGRAM_END: u32 = 0x4000;
+
+pub fn disassemble(
+    input_file_path: &String,
+    device: Option<&'static Device>,
+) -> Result<(), ProgrammerError> {
+    info!("reading file {}", input_file_path);
+    let image = MemoryImage::from_hex_file(&HexFile::read(input_file_path)?);
+
+    let Some(device) = device else {
+        print_instructions(&disassemble_pic14(&image, 0..PIC14_PROGRAM_END), 2);
+        return Ok(());
+    };
+
+    info!("target device {}", device);
+    let program = 0..device.program_words * 2;
+    match device.family {
+        Family::Pic12 | Family::Pic16 => print_instructions(&disassemble_pic14(&image, program), 2),
+        Family::Pic18 => print_instructions(&disassemble_pic18(&image, program), 1),
+        Family::Pic10 => {
+            return Err(ProgrammerError::Unsupported(format!(
+                "disassembly is not supported for {}",
+                device.name
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Prints word addresses for mid-range parts (`bytes_per_address` of 2) and byte addresses for PIC18.
+fn print_instructions(instructions: &[Instruction], bytes_per_address: u32) {
+    for instruction in instructions {
+        let words: Vec<String> = instruction
+            .words
+            .iter()
+            .map(|word| format!("{:04X}", word))
+            .collect();
+
+        println!(
+            "{:06X}  {:<10} {}",
+            instruction.address / bytes_per_address,
+            words.join(" "),
+            instruction.text
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::hex::record::{HexRecord, RecordErrorKind, RecordType};
+use std::{fmt, fs, io};
+
+#[derive(Debug)]
+pub enum HexError {
+    Io {
+        file: String,
+        error: io::Error,
+    },
+    Record {
+        file: String,
+        line: usize,
+        column: usize,
+        kind: RecordErrorKind,
+    },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::Io { file, error } => write!(f, "{}: {}", file, error),
+            HexError::Record {
+                file,
+                line,
+                column,
+                kind,
+            } => write!(f, "{}:{}:{}: {}", file, line, column, kind),
+        }
+    }
+}
+
+/// A decoded record together with the 1-based line it was read from.
+#[derive(Debug, Clone)]
+pub struct HexLine {
+    pub number: usize,
+    pub record: HexRecord,
+}
+
+/// Payload of a data record placed at its absolute address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataBlock<'a> {
+    pub line: usize,
+    pub address: u32,
+    pub data: &'a [u8],
+}
+
+#[derive(Debug, Clone)]
+pub struct HexFile {
+    pub lines: Vec<HexLine>,
+}
+
+impl HexFile {
+    pub fn read(path: &str) -> Result<HexFile, Vec<HexError>> {
+        let contents = fs::read_to_string(path).map_err(|error| {
+            vec![HexError::Io {
+                file: path.to_string(),
+                error,
+            }]
+        })?;
+
+        HexFile::parse(path, &contents)
+    }
+
+    /// Decodes every non-blank line, collecting all errors instead of stopping at the first one.
+    pub fn parse(path: &str, contents: &str) -> Result<HexFile, Vec<HexError>> {
+        let mut lines = vec![];
+        let mut errors = vec![];
+
+        for (index, line) in contents.lines().enumerate() {
+            let trimmed_line = line.trim();
+            if trimmed_line.is_empty() {
+                continue;
+            }
+
+            let leading_whitespace = line.len() - line.trim_start().len();
+            match HexRecord::parse(trimmed_line) {
+                Ok(record) => lines.push(HexLine {
+                    number: index + 1,
+                    record,
+                }),
+                Err(e) => errors.push(HexError::Record {
+                    file: path.to_string(),
+                    line: index + 1,
+                    column: leading_whitespace + e.column,
+                    kind: e.kind,
+                }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(HexFile { lines })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Data records up to the end of file record, with extended address records applied.
+    pub fn data_blocks(&self) -> Vec<DataBlock<'_>> {
+        let mut blocks = vec![];
+        let mut base_address = 0;
+
+        for line in &self.lines {
+            match line.record.record_type {
+                RecordType::Data => blocks.push(DataBlock {
+                    line: line.number,
+                    address: base_address + line.record.address as u32,
+                    data: &line.record.data,
+                }),
+                RecordType::EndOfFile => break,
+                _ => {
+                    if let Some(extended_address) = line.record.extended_address() {
+                        base_address = extended_address;
+                    }
+                }
+            }
+        }
+
+        blocks
+    }
+
+    /// Overwrites the byte at `address` in the data record holding it, returns `false` if no record does.
+    pub fn write_byte(&mut self, address: u32, value: u8) -> bool {
+        let mut base_address = 0;
+
+        for line in &mut self.lines {
+            let record = &mut line.record;
+            match record.record_type {
+                RecordType::Data => {
+                    let start = base_address + record.address as u32;
+                    let offset = address.wrapping_sub(start) as usize;
+                    if address >= start && offset < record.data.len() {
+                        record.data[offset] = value;
+                        return true;
+                    }
+                }
+                RecordType::EndOfFile => break,
+                _ => {
+                    if let Some(extended_address) = record.extended_address() {
+                        base_address = extended_address;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Adds a data record right before the end of file record, selecting its upper address bits first.
+    pub fn insert_data(&mut self, address: u32, data: Vec<u8>) {
+        let index = self
+            .lines
+            .iter()
+            .position(|line| line.record.record_type == RecordType::EndOfFile)
+            .unwrap_or(self.lines.len());
+        let number = self.lines.get(index).map_or(0, |line| line.number);
+
+        let records = [
+            HexRecord::new(
+                RecordType::ExtendedLinearAddress,
+                0,
+                ((address >> 16) as u16).to_be_bytes().to_vec(),
+            ),
+            HexRecord::new(RecordType::Data, address as u16, data),
+        ];
+        for (offset, record) in records.into_iter().enumerate() {
+            self.lines
+                .insert(index + offset, HexLine { number, record });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::hex_file::{DataBlock, HexError, HexFile};
+    use crate::hex::record::{RecordErrorKind, RecordType};
+
+    #[test]
+    fn given_blank_lines_should_skip_them_and_keep_line_numbers() {
+        let file = HexFile::parse("test.hex", "\n  :04001000831603133D\n\n:00000001FF\n").unwrap();
+
+        assert_eq!(file.lines.len(), 2);
+        assert_eq!(file.lines[0].number, 2);
+        assert_eq!(file.lines[1].number, 4);
+        assert_eq!(file.lines[1].record.record_type, RecordType::EndOfFile);
+    }
+
+    #[test]
+    fn given_multiple_bad_lines_should_report_all_of_them_with_position() {
+        let errors =
+            HexFile::parse("test.hex", ":04001000831603133E\n  :0400100083G603133D\n").unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            &errors[0],
+            HexError::Record {
+                line: 1,
+                column: 18,
+                kind: RecordErrorKind::ChecksumMismatch { .. },
+                ..
+            }
+        ));
+        assert!(matches!(
+            &errors[1],
+            HexError::Record {
+                line: 2,
+                column: 14,
+                kind: RecordErrorKind::InvalidHexDigit('G'),
+                ..
+            }
+        ));
+        assert_eq!(
+            errors[1].to_string(),
+            "test.hex:2:14: invalid hex digit 'G'"
+        );
+    }
+
+    #[test]
+    fn given_extended_linear_address_should_place_data_blocks_above_it() {
+        let file = HexFile::parse(
+            "test.hex",
+            ":0200000400F00A\n:02000000FF3FC0\n:00000001FF\n:02000200FF3FBE\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.data_blocks(),
+            vec![DataBlock {
+                line: 2,
+                address: 0xF00000,
+                data: &[0xFF, 0x3F],
+            }]
+        );
+    }
+
+    #[test]
+    fn given_byte_inside_data_record_should_overwrite_it() {
+        let mut file = HexFile::parse("test.hex", ":02400E00143F5D\n:00000001FF\n").unwrap();
+
+        assert!(file.write_byte(0x400E, 0x10));
+        assert!(!file.write_byte(0x4010, 0x10));
+        assert_eq!(file.lines[0].record.to_string(), ":02400E00103F61");
+    }
+
+    #[test]
+    fn given_missing_data_should_insert_it_before_end_of_file() {
+        let mut file = HexFile::parse("test.hex", ":02000000FF3FC0\n:00000001FF\n").unwrap();
+
+        file.insert_data(0x400E, vec![0x10, 0x3F]);
+
+        let records: Vec<String> = file.lines.iter().map(|l| l.record.to_string()).collect();
+        assert_eq!(
+            records,
+            vec![
+                ":02000000FF3FC0",
+                ":020000040000FA",
+                ":02400E00103F61",
+                ":00000001FF",
+            ]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::hex::hex_file::HexFile;
+use crate::hex::record::{HexRecord, RecordType};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+const BYTES_PER_RECORD: usize = 16;
+
+/// A program word whose content on the device differs from the expected image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMismatch {
+    pub address: u32,
+    pub expected: u16,
+    pub actual: Option<u16>,
+}
+
+/// Sparse byte-addressed view of everything the data records of a hex file describe.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryImage {
+    pub bytes: BTreeMap<u32, u8>,
+}
+
+impl MemoryImage {
+    pub fn from_hex_file(hex_file: &HexFile) -> MemoryImage {
+        let mut image = MemoryImage::default();
+
+        for block in hex_file.data_blocks() {
+            // Bytes past the 32-bit address space are dropped, `validate` reports them.
+            for (offset, byte) in block.data.iter().enumerate() {
+                if let Some(address) = block.address.checked_add(offset as u32) {
+                    image.bytes.insert(address, *byte);
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Encodes the image as data records, starting a new extended linear address
+    /// record whenever the upper 16 bits of the address change, followed by the end of file record.
+    pub fn to_records(&self) -> Vec<HexRecord> {
+        let mut records = vec![];
+        let mut upper_address = 0;
+        let mut chunk: Vec<u8> = vec![];
+        let mut chunk_address: u32 = 0;
+
+        for (&address, &byte) in &self.bytes {
+            let next_address = chunk_address.checked_add(chunk.len() as u32);
+            let continues_chunk = !chunk.is_empty()
+                && Some(address) == next_address
+                && chunk.len() < BYTES_PER_RECORD
+                && address >> 16 == chunk_address >> 16;
+
+            if !continues_chunk {
+                push_data_record(&mut records, &mut upper_address, chunk_address, &chunk);
+                chunk.clear();
+                chunk_address = address;
+            }
+
+            chunk.push(byte);
+        }
+
+        push_data_record(&mut records, &mut upper_address, chunk_address, &chunk);
+        records.push(HexRecord::new(RecordType::EndOfFile, 0, vec![]));
+
+        records
+    }
+
+    /// Compares every 16-bit little-endian word described by `self` against `actual`.
+    /// Words missing from `actual` are reported with no actual value.
+    pub fn compare_words(&self, actual: &MemoryImage) -> Vec<WordMismatch> {
+        let mut mismatches = vec![];
+        let mut word_addresses: Vec<u32> = self.bytes.keys().map(|address| address & !1).collect();
+        word_addresses.dedup();
+
+        for address in word_addresses {
+            let differs = (address..=address.saturating_add(1)).any(|byte_address| {
+                self.bytes
+                    .get(&byte_address)
+                    .is_some_and(|expected| actual.bytes.get(&byte_address) != Some(expected))
+            });
+
+            if differs {
+                mismatches.push(WordMismatch {
+                    address,
+                    expected: self.word(address).unwrap_or(0xFFFF),
+                    actual: actual.word(address),
+                });
+            }
+        }
+
+        mismatches
+    }
+
+    /// Word at `address` with missing bytes read as erased (0xFF), `None` if both are missing.
+    pub fn word(&self, address: u32) -> Option<u16> {
+        let low = self.bytes.get(&address);
+        let high = address
+            .checked_add(1)
+            .and_then(|address| self.bytes.get(&address));
+        if low.is_none() && high.is_none() {
+            return None;
+        }
+
+        Some(u16::from_le_bytes([
+            *low.unwrap_or(&0xFF),
+            *high.unwrap_or(&0xFF),
+        ]))
+    }
+
+    pub fn write_hex(&self, path: &str) -> io::Result<()> {
+        let contents: String = self
+            .to_records()
+            .iter()
+            .map(|record| format!("{}\n", record))
+            .collect();
+
+        fs::write(path, contents)
+    }
+}
+
+fn push_data_record(
+    records: &mut Vec<HexRecord>,
+    upper_address: &mut u32,
+    address: u32,
+    data: &[u8],
+) {
+    if data.is_empty() {
+        return;
+    }
+
+    if address >> 16 != *upper_address {
+        *upper_address = address >> 16;
+        records.push(HexRecord::new(
+            RecordType::ExtendedLinearAddress,
+            0,
+            (*upper_address as u16).to_be_bytes().to_vec(),
+        ));
+    }
+
+    records.push(HexRecord::new(
+        RecordType::Data,
+        address as u16,
+        data.to_vec(),
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::hex_file::HexFile;
+    use crate::hex::memory_image::{MemoryImage, WordMismatch};
+
+    #[test]
+    fn given_contiguous_bytes_should_split_them_in_records_of_sixteen_bytes() {
+        let mut image = MemoryImage::default();
+        for address in 0..18 {
+            image.bytes.insert(address, 0xFF);
+        }
+
+        let records: Vec<String> = image.to_records().iter().map(|r| r.to_string()).collect();
+
+        assert_eq!(
+            records,
+            vec![
+                ":10000000FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00",
+                ":02001000FFFFF0",
+                ":00000001FF",
+            ]
+        );
+    }
+
+    #[test]
+    fn given_bytes_above_64k_should_emit_extended_linear_address_records() {
+        let file = HexFile::parse(
+            "test.hex",
+            ":02000000FF3FC0\n:020000040030CA\n:01000000FF00\n:00000001FF\n",
+        )
+        .unwrap();
+
+        let records: Vec<String> = MemoryImage::from_hex_file(&file)
+            .to_records()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+
+        assert_eq!(
+            records,
+            vec![
+                ":02000000FF3FC0",
+                ":020000040030CA",
+                ":01000000FF00",
+                ":00000001FF",
+            ]
+        );
+    }
+
+    #[test]
+    fn given_gap_between_bytes_should_start_a_new_record() {
+        let file = HexFile::parse("test.hex", ":02000000FF3FC0\n:02000400FF3FBC\n").unwrap();
+
+        let image = MemoryImage::from_hex_file(&file);
+
+        assert_eq!(image.to_records().len(), 3);
+    }
+
+    #[test]
+    fn given_words_at_the_end_of_the_address_space_should_compare_and_encode_them() {
+        let mut expected = MemoryImage::default();
+        for address in 0xFFFF_FFF0..=0xFFFF_FFFF {
+            expected.bytes.insert(address, 0x00);
+        }
+        let mut actual = expected.clone();
+        actual.bytes.insert(0xFFFF_FFFF, 0x01);
+
+        let mismatches = expected.compare_words(&actual);
+
+        assert_eq!(
+            mismatches,
+            vec![WordMismatch {
+                address: 0xFFFF_FFFE,
+                expected: 0x0000,
+                actual: Some(0x0100),
+            }]
+        );
+        assert_eq!(expected.word(0xFFFF_FFFF), Some(0xFF00));
+        assert_eq!(expected.to_records().len(), 3);
+    }
+
+    #[test]
+    fn given_different_and_missing_words_should_report_them() {
+        let expected = HexFile::parse("test.hex", ":06000000FF3F8316FF3FE5\n").unwrap();
+        let actual = HexFile::parse("test.hex", ":04000000FF3F831229\n").unwrap();
+
+        let mismatches = MemoryImage::from_hex_file(&expected)
+            .compare_words(&MemoryImage::from_hex_file(&actual));
+
+        assert_eq!(
+            mismatches,
+            vec![
+                WordMismatch {
+                    address: 0x0002,
+                    expected: 0x1683,
+                    actual: Some(0x1283),
+                },
+                WordMismatch {
+                    address: 0x0004,
+                    expected: 0x3FFF,
+                    actual: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn given_identical_images_should_not_report_anything() {
+        let file = HexFile::parse("test.hex", ":06000000FF3F8316FF3FE5\n").unwrap();
+        let image = MemoryImage::from_hex_file(&file);
+
+        assert!(image.compare_words(&image.clone()).is_empty());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod hex_file;
+pub mod memory_image;
+pub mod record;
+pub mod validation;
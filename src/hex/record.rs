@@ -0,0 +1,344 @@
+use std::fmt;
+
+const START_CODE: char = ':';
+const HEADER_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    Data,
+    EndOfFile,
+    ExtendedSegmentAddress,
+    StartSegmentAddress,
+    ExtendedLinearAddress,
+    StartLinearAddress,
+}
+
+impl RecordType {
+    pub fn from_code(code: u8) -> Option<RecordType> {
+        match code {
+            0x00 => Some(RecordType::Data),
+            0x01 => Some(RecordType::EndOfFile),
+            0x02 => Some(RecordType::ExtendedSegmentAddress),
+            0x03 => Some(RecordType::StartSegmentAddress),
+            0x04 => Some(RecordType::ExtendedLinearAddress),
+            0x05 => Some(RecordType::StartLinearAddress),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            RecordType::Data => 0x00,
+            RecordType::EndOfFile => 0x01,
+            RecordType::ExtendedSegmentAddress => 0x02,
+            RecordType::StartSegmentAddress => 0x03,
+            RecordType::ExtendedLinearAddress => 0x04,
+            RecordType::StartLinearAddress => 0x05,
+        }
+    }
+
+    /// Payload length required by the record type, `None` for data records.
+    fn expected_data_length(&self) -> Option<usize> {
+        match self {
+            RecordType::Data => None,
+            RecordType::EndOfFile => Some(0),
+            RecordType::ExtendedSegmentAddress | RecordType::ExtendedLinearAddress => Some(2),
+            RecordType::StartSegmentAddress | RecordType::StartLinearAddress => Some(4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordErrorKind {
+    MissingStartCode,
+    InvalidHexDigit(char),
+    OddNumberOfDigits,
+    TooShort,
+    ByteCountMismatch {
+        declared: usize,
+        actual: usize,
+    },
+    UnknownRecordType(u8),
+    InvalidDataLength {
+        record_type: RecordType,
+        length: usize,
+    },
+    ChecksumMismatch {
+        expected: u8,
+        actual: u8,
+    },
+}
+
+/// A record that could not be decoded, `column` is 1-based and points into the trimmed line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    pub column: usize,
+    pub kind: RecordErrorKind,
+}
+
+impl fmt::Display for RecordErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordErrorKind::MissingStartCode => write!(f, "record does not start with ':'"),
+            RecordErrorKind::InvalidHexDigit(c) => write!(f, "invalid hex digit '{}'", c),
+            RecordErrorKind::OddNumberOfDigits => write!(f, "record has an odd number of digits"),
+            RecordErrorKind::TooShort => write!(f, "record is too short"),
+            RecordErrorKind::ByteCountMismatch { declared, actual } => write!(
+                f,
+                "byte count is {} but the record contains {} data bytes",
+                declared, actual
+            ),
+            RecordErrorKind::UnknownRecordType(code) => {
+                write!(f, "unknown record type {:02X}", code)
+            }
+            RecordErrorKind::InvalidDataLength {
+                record_type,
+                length,
+            } => write!(
+                f,
+                "record type {:02X} cannot have {} data bytes",
+                record_type.code(),
+                length
+            ),
+            RecordErrorKind::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum is {:02X} but should be {:02X}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexRecord {
+    pub record_type: RecordType,
+    pub address: u16,
+    pub data: Vec<u8>,
+}
+
+impl HexRecord {
+    pub fn new(record_type: RecordType, address: u16, data: Vec<u8>) -> HexRecord {
+        HexRecord {
+            record_type,
+            address,
+            data,
+        }
+    }
+
+    pub fn checksum(&self) -> u8 {
+        let sum = self
+            .data
+            .iter()
+            .fold(self.data.len() as u8, |sum, byte| sum.wrapping_add(*byte))
+            .wrapping_add((self.address >> 8) as u8)
+            .wrapping_add(self.address as u8)
+            .wrapping_add(self.record_type.code());
+
+        sum.wrapping_neg()
+    }
+
+    pub fn parse(line: &str) -> Result<HexRecord, RecordError> {
+        let digits = line
+            .strip_prefix(START_CODE)
+            .ok_or_else(|| error(1, RecordErrorKind::MissingStartCode))?;
+
+        let bytes = decode_digits(digits)?;
+        if bytes.len() < HEADER_LENGTH + 1 {
+            return Err(error(line.len() + 1, RecordErrorKind::TooShort));
+        }
+
+        let declared = bytes[0] as usize;
+        let actual = bytes.len() - HEADER_LENGTH - 1;
+        if declared != actual {
+            return Err(error(
+                2,
+                RecordErrorKind::ByteCountMismatch { declared, actual },
+            ));
+        }
+
+        let record_type = RecordType::from_code(bytes[3])
+            .ok_or_else(|| error(8, RecordErrorKind::UnknownRecordType(bytes[3])))?;
+
+        if let Some(expected_length) = record_type.expected_data_length() {
+            if expected_length != actual {
+                return Err(error(
+                    2,
+                    RecordErrorKind::InvalidDataLength {
+                        record_type,
+                        length: actual,
+                    },
+                ));
+            }
+        }
+
+        let record = HexRecord::new(
+            record_type,
+            u16::from_be_bytes([bytes[1], bytes[2]]),
+            bytes[HEADER_LENGTH..bytes.len() - 1].to_vec(),
+        );
+
+        let checksum = bytes[bytes.len() - 1];
+        if record.checksum() != checksum {
+            return Err(error(
+                line.len() - 1,
+                RecordErrorKind::ChecksumMismatch {
+                    expected: record.checksum(),
+                    actual: checksum,
+                },
+            ));
+        }
+
+        Ok(record)
+    }
+
+    /// Upper address bits set by an extended address record.
+    pub fn extended_address(&self) -> Option<u32> {
+        let value = u16::from_be_bytes([*self.data.first()?, *self.data.get(1)?]) as u32;
+
+        match self.record_type {
+            RecordType::ExtendedSegmentAddress => Some(value << 4),
+            RecordType::ExtendedLinearAddress => Some(value << 16),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HexRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{:02X}{:04X}{:02X}",
+            START_CODE,
+            self.data.len(),
+            self.address,
+            self.record_type.code()
+        )?;
+
+        for byte in &self.data {
+            write!(f, "{:02X}", byte)?;
+        }
+
+        write!(f, "{:02X}", self.checksum())
+    }
+}
+
+fn decode_digits(digits: &str) -> Result<Vec<u8>, RecordError> {
+    if let Some((index, c)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(error(index + 2, RecordErrorKind::InvalidHexDigit(c)));
+    }
+
+    if !digits.len().is_multiple_of(2) {
+        return Err(error(digits.len() + 1, RecordErrorKind::OddNumberOfDigits));
+    }
+
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+fn error(column: usize, kind: RecordErrorKind) -> RecordError {
+    RecordError { column, kind }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::record::{HexRecord, RecordErrorKind, RecordType};
+
+    #[test]
+    fn given_data_record_should_decode_address_and_data() {
+        let record = HexRecord::parse(":04001000831603133D").unwrap();
+
+        assert_eq!(record.record_type, RecordType::Data);
+        assert_eq!(record.address, 0x0010);
+        assert_eq!(record.data, vec![0x83, 0x16, 0x03, 0x13]);
+    }
+
+    #[test]
+    fn given_every_record_type_should_decode_it() {
+        let records = [
+            (":00000001FF", RecordType::EndOfFile),
+            (":020000021000EC", RecordType::ExtendedSegmentAddress),
+            (":0400000300003800C1", RecordType::StartSegmentAddress),
+            (":020000040030CA", RecordType::ExtendedLinearAddress),
+            (":04000005000000CD2A", RecordType::StartLinearAddress),
+        ];
+
+        for (line, record_type) in records {
+            assert_eq!(HexRecord::parse(line).unwrap().record_type, record_type);
+        }
+    }
+
+    #[test]
+    fn given_lowercase_record_should_encode_it_back_in_uppercase() {
+        let record = HexRecord::parse(":020000040030ca").unwrap();
+
+        assert_eq!(record.to_string(), ":020000040030CA");
+    }
+
+    #[test]
+    fn given_wrong_checksum_should_report_expected_checksum() {
+        let error = HexRecord::parse(":04001000831603133E").unwrap_err();
+
+        assert_eq!(error.column, 18);
+        assert_eq!(
+            error.kind,
+            RecordErrorKind::ChecksumMismatch {
+                expected: 0x3D,
+                actual: 0x3E
+            }
+        );
+    }
+
+    #[test]
+    fn given_byte_count_not_matching_payload_should_report_mismatch() {
+        let error = HexRecord::parse(":02002400BE1C").unwrap_err();
+
+        assert_eq!(error.column, 2);
+        assert_eq!(
+            error.kind,
+            RecordErrorKind::ByteCountMismatch {
+                declared: 2,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn given_invalid_digit_should_report_its_column() {
+        let error = HexRecord::parse(":0400100083G603133D").unwrap_err();
+
+        assert_eq!(error.column, 12);
+        assert_eq!(error.kind, RecordErrorKind::InvalidHexDigit('G'));
+    }
+
+    #[test]
+    fn given_missing_start_code_should_report_first_column() {
+        let error = HexRecord::parse("00000001FF").unwrap_err();
+
+        assert_eq!(error.column, 1);
+        assert_eq!(error.kind, RecordErrorKind::MissingStartCode);
+    }
+
+    #[test]
+    fn given_end_of_file_with_data_should_report_invalid_length() {
+        let error = HexRecord::parse(":01000001AA54").unwrap_err();
+
+        assert_eq!(
+            error.kind,
+            RecordErrorKind::InvalidDataLength {
+                record_type: RecordType::EndOfFile,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn given_extended_address_records_should_compute_upper_address() {
+        let segment = HexRecord::parse(":020000021000EC").unwrap();
+        let linear = HexRecord::parse(":020000040030CA").unwrap();
+
+        assert_eq!(segment.extended_address(), Some(0x10000));
+        assert_eq!(linear.extended_address(), Some(0x300000));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::hex::hex_file::HexFile;
+use crate::hex::record::RecordType;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    OverlappingAddress {
+        line: usize,
+        address: u32,
+        previous_line: usize,
+    },
+    RecordAfterEndOfFile {
+        line: usize,
+    },
+    MissingEndOfFile,
+    AddressOutOfRange {
+        line: usize,
+        address: u32,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::OverlappingAddress {
+                line,
+                address,
+                previous_line,
+            } => write!(
+                f,
+                "line {}: address 0x{:06X} was already written by line {}",
+                line, address, previous_line
+            ),
+            ValidationIssue::RecordAfterEndOfFile { line } => {
+                write!(f, "line {}: record after the end of file record", line)
+            }
+            ValidationIssue::MissingEndOfFile => write!(f, "missing end of file record"),
+            ValidationIssue::AddressOutOfRange { line, address } => write!(
+                f,
+                "line {}: address 0x{:06X} is outside of the device memory",
+                line, address
+            ),
+        }
+    }
+}
+
+/// Checks the structure of an already parsed file. An empty `memory` list accepts any address.
+pub fn validate(hex_file: &HexFile, memory: &[Range<u32>]) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+
+    match hex_file
+        .lines
+        .iter()
+        .position(|line| line.record.record_type == RecordType::EndOfFile)
+    {
+        Some(index) => {
+            for line in &hex_file.lines[index + 1..] {
+                issues.push(ValidationIssue::RecordAfterEndOfFile { line: line.number });
+            }
+        }
+        None => issues.push(ValidationIssue::MissingEndOfFile),
+    }
+
+    let mut written_by: BTreeMap<u32, usize> = BTreeMap::new();
+    for block in hex_file.data_blocks() {
+        // An extended linear address near the top can push a record past the 32-bit address space.
+        let Some(end) = block.address.checked_add(block.data.len() as u32) else {
+            issues.push(ValidationIssue::AddressOutOfRange {
+                line: block.line,
+                address: block.address,
+            });
+            continue;
+        };
+        let addresses = block.address..end;
+
+        if let Some((&address, &previous_line)) = written_by.range(addresses.clone()).next() {
+            issues.push(ValidationIssue::OverlappingAddress {
+                line: block.line,
+                address,
+                previous_line,
+            });
+        }
+
+        if !memory.is_empty() {
+            if let Some(address) = addresses
+                .clone()
+                .find(|address| !memory.iter().any(|range| range.contains(address)))
+            {
+                issues.push(ValidationIssue::AddressOutOfRange {
+                    line: block.line,
+                    address,
+                });
+            }
+        }
+
+        for address in addresses {
+            written_by.entry(address).or_insert(block.line);
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::hex_file::HexFile;
+    use crate::hex::validation::{validate, ValidationIssue};
+
+    #[test]
+    fn given_well_formed_file_should_not_report_anything() {
+        let file = HexFile::parse("test.hex", ":02000000FF3FC0\n:00000001FF\n").unwrap();
+
+        assert!(validate(&file, &[0..0x800, 0x400E..0x4010]).is_empty());
+    }
+
+    #[test]
+    fn given_file_without_end_of_file_should_report_it() {
+        let file = HexFile::parse("test.hex", ":02000000FF3FC0\n").unwrap();
+
+        assert_eq!(
+            validate(&file, &[]),
+            vec![ValidationIssue::MissingEndOfFile]
+        );
+    }
+
+    #[test]
+    fn given_records_after_end_of_file_should_report_each_of_them() {
+        let file = HexFile::parse(
+            "test.hex",
+            ":00000001FF\n:02000000FF3FC0\n:02000200FF3FBE\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate(&file, &[]),
+            vec![
+                ValidationIssue::RecordAfterEndOfFile { line: 2 },
+                ValidationIssue::RecordAfterEndOfFile { line: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn given_overlapping_records_should_report_first_overlapping_address() {
+        let file = HexFile::parse(
+            "test.hex",
+            ":04000000FF3FFF3F80\n:02000200FF3FBE\n:00000001FF\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate(&file, &[]),
+            vec![ValidationIssue::OverlappingAddress {
+                line: 2,
+                address: 0x0002,
+                previous_line: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn given_address_outside_of_memory_should_report_it() {
+        let file = HexFile::parse("test.hex", ":04000000FF3FFF3F80\n:00000001FF\n").unwrap();
+
+        assert_eq!(
+            validate(&file, &[0..0x0002, 0x400E..0x4010]),
+            vec![ValidationIssue::AddressOutOfRange {
+                line: 1,
+                address: 0x0002,
+            }]
+        );
+    }
+
+    #[test]
+    fn given_record_past_the_address_space_should_report_it() {
+        let file = HexFile::parse(
+            "test.hex",
+            ":02000004FFFFFC\n:04FFFE0001020304F5\n:00000001FF\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate(&file, &[]),
+            vec![ValidationIssue::AddressOutOfRange {
+                line: 2,
+                address: 0xFFFF_FFFE,
+            }]
+        );
+    }
+}
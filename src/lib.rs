@@ -0,0 +1,29 @@
+//! Library behind the `pic-programmer-cli` binary. It talks to an Arduino running the PIC programmer
+//! sketch over a serial port and models Intel HEX files and the supported PIC devices.
+//!
+//! ```no_run
+//! use pic_programmer_cli::hex::hex_file::HexFile;
+//! use pic_programmer_cli::programmer::connection::Connection;
+//! use pic_programmer_cli::programmer::serial_programmer::SerialProgrammer;
+//! use pic_programmer_cli::programmer::serial_reader::SerialReader;
+//! use pic_programmer_cli::programmer::serial_writer::SerialWriter;
+//!
+//! let hex_file = HexFile::read("firmware.hex").unwrap();
+//! let mut port = Connection::new("/dev/ttyUSB0", 57600, 5000).open().unwrap();
+//! let mut programmer = SerialProgrammer::new(SerialReader::new(vec![0; 1000]), SerialWriter::new());
+//!
+//! programmer.program(&mut port, &hex_file).unwrap();
+//! let lines = programmer.read_back(&mut port).unwrap();
+//! ```
+
+pub mod blank_check;
+pub mod config_word;
+pub mod device;
+pub mod disasm;
+pub mod disassemble;
+pub mod hex;
+pub mod list_ports;
+pub mod log;
+pub mod programmer;
+pub mod show_config;
+pub mod validate;
@@ -1,15 +1,301 @@
-pub fn list_ports() {
-    match serialport::available_ports() {
-        Ok(ports) => {
-            if ports.is_empty() {
-                println!("no ports detected");
-                return;
-            }
+use crate::programmer::error::ProgrammerError;
+use serialport::{SerialPortInfo, SerialPortType};
+use std::{fmt, io};
+
+/// USB ids of boards that are likely Arduinos, `None` matching every product of the vendor.
+const ARDUINO_IDS: [(u16, Option<u16>); 3] = [
+    // Arduino LLC
+    (0x2341, None),
+    // Arduino SRL
+    (0x2A03, None),
+    // CH340, used by most Arduino clones
+    (0x1A86, Some(0x7523)),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortFilter {
+    pub usb_only: bool,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+}
+
+impl PortFilter {
+    pub fn matches(&self, port: &PortDescription) -> bool {
+        let usb = port.usb.as_ref();
+        if (self.usb_only || self.vid.is_some() || self.pid.is_some()) && usb.is_none() {
+            return false;
+        }
+
+        usb.is_none_or(|usb| {
+            self.vid.is_none_or(|vid| usb.vid == vid) && self.pid.is_none_or(|pid| usb.pid == pid)
+        })
+    }
+}
 
-            for p in ports {
-                println!("{}", p.port_name);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbDescription {
+    pub vid: u16,
+    pub pid: u16,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+impl UsbDescription {
+    pub fn is_arduino(&self) -> bool {
+        ARDUINO_IDS
+            .iter()
+            .any(|(vid, pid)| *vid == self.vid && pid.is_none_or(|pid| pid == self.pid))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortDescription {
+    pub name: String,
+    pub kind: &'static str,
+    pub usb: Option<UsbDescription>,
+}
+
+impl PortDescription {
+    pub fn is_arduino(&self) -> bool {
+        self.usb.as_ref().is_some_and(UsbDescription::is_arduino)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            format!("\"name\":{}", json_string(&self.name)),
+            format!("\"type\":{}", json_string(self.kind)),
+        ];
+        if let Some(usb) = &self.usb {
+            fields.push(format!("\"vid\":\"{:04x}\"", usb.vid));
+            fields.push(format!("\"pid\":\"{:04x}\"", usb.pid));
+            fields.push(format!(
+                "\"manufacturer\":{}",
+                json_optional(&usb.manufacturer)
+            ));
+            fields.push(format!("\"product\":{}", json_optional(&usb.product)));
+            fields.push(format!(
+                "\"serial_number\":{}",
+                json_optional(&usb.serial_number)
+            ));
+        }
+        fields.push(format!("\"arduino\":{}", self.is_arduino()));
+
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+impl From<SerialPortInfo> for PortDescription {
+    fn from(info: SerialPortInfo) -> Self {
+        let (kind, usb) = match info.port_type {
+            SerialPortType::UsbPort(usb) => (
+                "USB",
+                Some(UsbDescription {
+                    vid: usb.vid,
+                    pid: usb.pid,
+                    manufacturer: usb.manufacturer,
+                    product: usb.product,
+                    serial_number: usb.serial_number,
+                }),
+            ),
+            SerialPortType::PciPort => ("PCI", None),
+            SerialPortType::BluetoothPort => ("Bluetooth", None),
+            SerialPortType::Unknown => ("Unknown", None),
+        };
+
+        PortDescription {
+            name: info.port_name,
+            kind,
+            usb,
+        }
+    }
+}
+
+impl fmt::Display for PortDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.kind)?;
+        if let Some(usb) = &self.usb {
+            write!(f, " {:04x}:{:04x}", usb.vid, usb.pid)?;
+            let names: Vec<&str> = [&usb.manufacturer, &usb.product]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            if !names.is_empty() {
+                write!(f, " {}", names.join(" - "))?;
+            }
+            if let Some(serial_number) = &usb.serial_number {
+                write!(f, ", serial {}", serial_number)?;
             }
         }
-        Err(e) => eprintln!("Error listing ports: {:?}", e),
+        if self.is_arduino() {
+            write!(f, " [Arduino]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a hexadecimal USB vendor or product id, with or without `0x` prefix.
+pub fn parse_usb_id(value: &str) -> Result<u16, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    u16::from_str_radix(digits, 16)
+        .map_err(|_| format!("expected a hexadecimal USB id (e.g., 2341), got {}", value))
+}
+
+pub fn list_ports(filter: &PortFilter, json: bool) -> Result<(), ProgrammerError> {
+    let ports = serialport::available_ports().map_err(|e| {
+        ProgrammerError::Serial(io::Error::new(
+            io::Error::from(e.clone()).kind(),
+            format!("failed to list ports: {}", e),
+        ))
+    })?;
+    let ports: Vec<PortDescription> = ports
+        .into_iter()
+        .map(PortDescription::from)
+        .filter(|port| filter.matches(port))
+        .collect();
+
+    if json {
+        println!("{}", ports_to_json(&ports));
+        return Ok(());
+    }
+
+    if ports.is_empty() {
+        println!("no ports detected");
+        return Ok(());
+    }
+
+    for p in ports {
+        println!("{}", p);
+    }
+    Ok(())
+}
+
+pub fn ports_to_json(ports: &[PortDescription]) -> String {
+    let ports: Vec<String> = ports.iter().map(PortDescription::to_json).collect();
+    format!("[{}]", ports.join(","))
+}
+
+fn json_optional(value: &Option<String>) -> String {
+    value.as_deref().map_or(String::from("null"), json_string)
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use crate::list_ports::{
+        parse_usb_id, ports_to_json, PortDescription, PortFilter, UsbDescription,
+    };
+
+    fn usb_port(name: &str, vid: u16, pid: u16) -> PortDescription {
+        PortDescription {
+            name: name.to_string(),
+            kind: "USB",
+            usb: Some(UsbDescription {
+                vid,
+                pid,
+                manufacturer: Some(String::from("Arduino (www.arduino.cc)")),
+                product: None,
+                serial_number: Some(String::from("85739313")),
+            }),
+        }
+    }
+
+    fn pci_port(name: &str) -> PortDescription {
+        PortDescription {
+            name: name.to_string(),
+            kind: "PCI",
+            usb: None,
+        }
+    }
+
+    #[test]
+    fn given_known_ids_should_flag_arduino_boards() {
+        assert!(usb_port("ttyACM0", 0x2341, 0x0043).is_arduino());
+        assert!(usb_port("ttyUSB0", 0x1A86, 0x7523).is_arduino());
+        assert!(!usb_port("ttyUSB1", 0x0403, 0x6001).is_arduino());
+        assert!(!pci_port("ttyS0").is_arduino());
+    }
+
+    #[test]
+    fn given_filter_should_keep_matching_ports_only() {
+        let ports = [
+            usb_port("ttyACM0", 0x2341, 0x0043),
+            usb_port("ttyUSB0", 0x0403, 0x6001),
+            pci_port("ttyS0"),
+        ];
+        let names = |filter: PortFilter| -> Vec<&str> {
+            ports
+                .iter()
+                .filter(|port| filter.matches(port))
+                .map(|port| port.name.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            names(PortFilter::default()),
+            vec!["ttyACM0", "ttyUSB0", "ttyS0"]
+        );
+        assert_eq!(
+            names(PortFilter {
+                usb_only: true,
+                ..PortFilter::default()
+            }),
+            vec!["ttyACM0", "ttyUSB0"]
+        );
+        assert_eq!(
+            names(PortFilter {
+                vid: Some(0x0403),
+                ..PortFilter::default()
+            }),
+            vec!["ttyUSB0"]
+        );
+        assert_eq!(
+            names(PortFilter {
+                vid: Some(0x2341),
+                pid: Some(0x0001),
+                ..PortFilter::default()
+            }),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn given_ports_should_write_json() {
+        let json = ports_to_json(&[usb_port("COM\"3", 0x2341, 0x0043), pci_port("COM1")]);
+
+        assert_eq!(
+            json,
+            "[{\"name\":\"COM\\\"3\",\"type\":\"USB\",\"vid\":\"2341\",\"pid\":\"0043\",\
+            \"manufacturer\":\"Arduino (www.arduino.cc)\",\"product\":null,\
+            \"serial_number\":\"85739313\",\"arduino\":true},\
+            {\"name\":\"COM1\",\"type\":\"PCI\",\"arduino\":false}]"
+        );
+    }
+
+    #[test]
+    fn given_usb_id_should_parse_hexadecimal() {
+        assert_eq!(parse_usb_id("2341"), Ok(0x2341));
+        assert_eq!(parse_usb_id("0x1a86"), Ok(0x1A86));
+        assert!(parse_usb_id("COM3").is_err());
     }
 }
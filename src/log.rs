@@ -0,0 +1,295 @@
+//! Logging of the CLI and of the output relayed from the programmer. Messages at or above the
+//! console level are printed, errors and warnings to stderr, and every message is written with a
+//! timestamp to the log file when there is one. Until [`Logger::install`] is called the console
+//! shows info messages without timestamps.
+
+#[cfg(test)]
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Console level used when neither `-v` nor `-q` is given.
+pub const DEFAULT_LEVEL: Level = Level::Info;
+
+#[cfg(test)]
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<(Level, Source, String)>>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        })
+    }
+}
+
+/// Who a message comes from, printed as the `[CLI]` or `[Programmer]` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Cli,
+    Programmer,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Cli => write!(f, "CLI"),
+            Source::Programmer => write!(f, "Programmer"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Logger {
+    level: Level,
+    timestamps: bool,
+    file: Option<Mutex<File>>,
+}
+
+impl Logger {
+    pub fn new(level: Level) -> Logger {
+        Logger {
+            level,
+            timestamps: false,
+            file: None,
+        }
+    }
+
+    pub fn with_timestamps(mut self, timestamps: bool) -> Logger {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Writes every message, whatever the console level, to the file at `path`, appending to it.
+    pub fn with_file(mut self, path: &str) -> io::Result<Logger> {
+        let file = File::options().create(true).append(true).open(path)?;
+        self.file = Some(Mutex::new(file));
+        Ok(self)
+    }
+
+    /// Makes this the logger of the process, a logger installed before is kept.
+    pub fn install(self) {
+        let _ = LOGGER.set(self);
+    }
+
+    fn log(&self, level: Level, source: Source, message: fmt::Arguments) {
+        let on_console = level <= self.level;
+        if !on_console && self.file.is_none() {
+            return;
+        }
+
+        let message = message.to_string();
+        let timestamp = timestamp(SystemTime::now());
+
+        if on_console {
+            let timestamp = self.timestamps.then_some(timestamp.as_str());
+            let line = console_line(timestamp, level, source, &message);
+            if level <= Level::Warn {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "{}", file_line(&timestamp, level, source, &message));
+            }
+        }
+    }
+}
+
+fn logger() -> &'static Logger {
+    LOGGER.get_or_init(|| Logger::new(DEFAULT_LEVEL))
+}
+
+/// Whether messages of `level` are printed on the console.
+pub fn shown_on_console(level: Level) -> bool {
+    level <= logger().level
+}
+
+pub fn log(level: Level, source: Source, message: fmt::Arguments) {
+    #[cfg(test)]
+    CAPTURED.with(|captured| {
+        if let Some(messages) = captured.borrow_mut().as_mut() {
+            messages.push((level, source, message.to_string()));
+        }
+    });
+
+    logger().log(level, source, message);
+}
+
+/// Runs `f` and returns the messages it logged on the current thread.
+#[cfg(test)]
+pub fn capture(f: impl FnOnce()) -> Vec<(Level, Source, String)> {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(vec![]));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap_or_default())
+}
+
+fn console_line(timestamp: Option<&str>, level: Level, source: Source, message: &str) -> String {
+    let timestamp = timestamp.map_or(String::new(), |timestamp| format!("{} ", timestamp));
+    match level {
+        Level::Info => format!("{}[{}] {}", timestamp, source, message),
+        _ => format!("{}[{}] {}: {}", timestamp, source, level, message),
+    }
+}
+
+fn file_line(timestamp: &str, level: Level, source: Source, message: &str) -> String {
+    format!("{} {:<5} [{}] {}", timestamp, level, source, message)
+}
+
+/// UTC time formatted as `2024-05-01T12:30:05.123Z`.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let seconds_of_day = seconds % 86400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Converts days since 1970-01-01 to a date of the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[macro_export]
+macro_rules! error {
+    (programmer: $($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Error, $crate::log::Source::Programmer, format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Error, $crate::log::Source::Cli, format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    (programmer: $($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Warn, $crate::log::Source::Programmer, format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Warn, $crate::log::Source::Cli, format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    (programmer: $($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Info, $crate::log::Source::Programmer, format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Info, $crate::log::Source::Cli, format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    (programmer: $($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Debug, $crate::log::Source::Programmer, format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Debug, $crate::log::Source::Cli, format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    (programmer: $($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Trace, $crate::log::Source::Programmer, format_args!($($arg)+))
+    };
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Trace, $crate::log::Source::Cli, format_args!($($arg)+))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::log::{civil_from_days, console_line, file_line, timestamp, Level, Source};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn given_days_since_epoch_should_convert_to_date() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20574), (2026, 5, 1));
+    }
+
+    #[test]
+    fn given_time_should_format_utc_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_714_566_605_123);
+
+        assert_eq!(timestamp(time), "2024-05-01T12:30:05.123Z");
+    }
+
+    #[test]
+    fn given_levels_should_format_console_and_file_lines() {
+        assert_eq!(
+            console_line(None, Level::Info, Source::Cli, "read started"),
+            "[CLI] read started"
+        );
+        assert_eq!(
+            console_line(
+                Some("2024-05-01T12:30:05.123Z"),
+                Level::Debug,
+                Source::Programmer,
+                "raw data: 'Y'"
+            ),
+            "2024-05-01T12:30:05.123Z [Programmer] debug: raw data: 'Y'"
+        );
+        assert_eq!(
+            file_line(
+                "2024-05-01T12:30:05.123Z",
+                Level::Warn,
+                Source::Cli,
+                "skipping COM1"
+            ),
+            "2024-05-01T12:30:05.123Z warn  [CLI] skipping COM1"
+        );
+    }
+}
@@ -1,32 +1,100 @@
 mod commands;
-mod list_ports;
-mod programmer;
 
 use crate::commands::Commands;
-use crate::list_ports::list_ports;
-use crate::programmer::program_pic::program_pic;
-use crate::programmer::read_pic::read_pic;
 use clap::Parser;
+use pic_programmer_cli::disassemble::disassemble;
+use pic_programmer_cli::error;
+use pic_programmer_cli::list_ports::{list_ports, PortFilter};
+use pic_programmer_cli::log::Logger;
+use pic_programmer_cli::programmer::blank_check_pic::blank_check_pic;
+use pic_programmer_cli::programmer::connection::Connection;
+use pic_programmer_cli::programmer::erase_pic::erase_pic;
+use pic_programmer_cli::programmer::error::ProgrammerError;
+use pic_programmer_cli::programmer::identify_pic::identify_pic;
+use pic_programmer_cli::programmer::program_pic::program_pic;
+use pic_programmer_cli::programmer::read_pic::read_pic;
+use pic_programmer_cli::programmer::resend_policy::ResendPolicy;
+use pic_programmer_cli::programmer::verify_pic::verify_pic;
+use pic_programmer_cli::show_config::show_config;
+use pic_programmer_cli::validate::validate_file;
 
 fn main() {
     let cli = commands::Cli::parse();
 
-    match &cli.command {
-        Commands::ListPorts => list_ports(),
+    let logger = Logger::new(cli.log_level()).with_timestamps(cli.timestamps);
+    let logger = match &cli.log_file {
+        Some(path) => logger.with_file(path).unwrap_or_else(|e| {
+            let error = ProgrammerError::File(format!("failed to open log file {}: {}", path, e));
+            error!("{}", error);
+            std::process::exit(error.exit_code());
+        }),
+        None => logger,
+    };
+    logger.install();
+
+    let result = match &cli.command {
+        Commands::ListPorts {
+            usb_only,
+            vid,
+            pid,
+            json,
+        } => list_ports(
+            &PortFilter {
+                usb_only: *usb_only,
+                vid: *vid,
+                pid: *pid,
+            },
+            *json,
+        ),
         Commands::Program {
             input_file_path,
-            port_name,
-            baud_rate,
-            timeout,
-            verbose,
-        } => {
-            program_pic(input_file_path, port_name, *baud_rate, *timeout, *verbose);
-        }
+            connection,
+            resend,
+            device,
+            config_overrides,
+            verify,
+        } => program_pic(
+            input_file_path,
+            &Connection::from(connection),
+            ResendPolicy::from(resend),
+            *device,
+            config_overrides,
+            *verify,
+        ),
         Commands::PrintProgram {
-            port_name,
-            baud_rate,
-            timeout,
-            verbose,
-        } => read_pic(port_name, *baud_rate, *timeout, *verbose),
+            connection,
+            output,
+            device,
+        } => read_pic(&Connection::from(connection), output, *device),
+        Commands::Verify {
+            input_file_path,
+            connection,
+        } => verify_pic(input_file_path, &Connection::from(connection)),
+        Commands::Validate {
+            input_file_path,
+            device,
+        } => validate_file(input_file_path, *device),
+        Commands::Config {
+            input_file_path,
+            device,
+        } => show_config(input_file_path, device),
+        Commands::Disasm {
+            input_file_path,
+            device,
+        } => disassemble(input_file_path, *device),
+        Commands::BlankCheck { connection, device } => {
+            blank_check_pic(&Connection::from(connection), device)
+        }
+        Commands::Erase { connection, mode } => {
+            erase_pic(&Connection::from(connection), (*mode).into())
+        }
+        Commands::Identify { connection } => identify_pic(&Connection::from(connection)),
+    };
+
+    if let Err(error) = result {
+        for line in error.to_string().lines() {
+            error!("{}", line);
+        }
+        std::process::exit(error.exit_code());
     }
 }
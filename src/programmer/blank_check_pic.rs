@@ -0,0 +1,84 @@
+use crate::blank_check::{check_blank, RegionReport};
+use crate::device::Device;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+
+use crate::programmer::read_pic::image_from_lines;
+use crate::{error, info};
+
+const LISTED_ADDRESSES: usize = 8;
+
+pub fn blank_check_pic(connection: &Connection, device: &Device) -> Result<(), ProgrammerError> {
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "blank checking {0} on Arduino at {1} with baud rate {2}...",
+        device, port_name, baud_rate
+    );
+
+    let mut port = connection.open()?;
+    let requirements = Requirements {
+        commands: vec!['D'],
+        family: Some(device.family),
+    };
+    let lines = connection.programmer(requirements).read(&mut port)?;
+    blank_check(device, &lines)
+}
+
+fn blank_check(device: &Device, lines: &[String]) -> Result<(), ProgrammerError> {
+    let image = image_from_lines(lines)?;
+
+    let reports = check_blank(device, &image);
+    for report in &reports {
+        print_report(report);
+    }
+
+    if reports.iter().all(|report| report.is_blank()) {
+        info!("{} is blank", device.name);
+        Ok(())
+    } else {
+        Err(ProgrammerError::Verification(format!(
+            "{} is not blank",
+            device.name
+        )))
+    }
+}
+
+fn print_report(report: &RegionReport) {
+    if report.is_blank() {
+        info!(
+            "{}: blank ({} locations checked)",
+            report.kind, report.checked
+        );
+        return;
+    }
+
+    if report.checked == 0 && report.missing.is_empty() {
+        error!("{}: no locations checked", report.kind);
+        return;
+    }
+
+    if !report.non_blank.is_empty() {
+        error!(
+            "{}: {} of {} locations are not blank",
+            report.kind,
+            report.non_blank.len(),
+            report.checked
+        );
+        for (address, value) in report.non_blank.iter().take(LISTED_ADDRESSES) {
+            error!("    0x{:06X} = 0x{:04X}", address, value);
+        }
+    }
+
+    if !report.missing.is_empty() {
+        error!(
+            "{}: {} locations missing from the read-back",
+            report.kind,
+            report.missing.len()
+        );
+        for address in report.missing.iter().take(LISTED_ADDRESSES) {
+            error!("    0x{:06X}", address);
+        }
+    }
+}
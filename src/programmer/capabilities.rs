@@ -0,0 +1,209 @@
+use crate::device::Family;
+use std::fmt;
+
+const FAMILIES: [Family; 4] = [Family::Pic10, Family::Pic12, Family::Pic16, Family::Pic18];
+
+/// Answer of the firmware to the identify command. Fields the firmware does not report are `None`
+/// and are not checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub version: String,
+    pub commands: Option<Vec<char>>,
+    pub max_line_length: Option<usize>,
+    pub families: Option<Vec<Family>>,
+}
+
+/// What a session needs from the firmware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirements {
+    pub commands: Vec<char>,
+    pub family: Option<Family>,
+}
+
+impl Capabilities {
+    /// Parses the `key=value` lines sent between `start` and `done`, unknown keys are ignored.
+    pub fn parse(lines: &[String]) -> Result<Capabilities, String> {
+        let mut capabilities = Capabilities {
+            version: String::from("unknown"),
+            commands: None,
+            max_line_length: None,
+            families: None,
+        };
+
+        for line in lines {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim() {
+                "version" => capabilities.version = value.to_string(),
+                "commands" => {
+                    capabilities.commands =
+                        Some(value.chars().filter(|c| !c.is_whitespace()).collect())
+                }
+                "max-line" => {
+                    let length = value
+                        .parse()
+                        .map_err(|_| format!("invalid max-line '{}'", value))?;
+                    capabilities.max_line_length = Some(length);
+                }
+                "families" => {
+                    let families = value
+                        .split(',')
+                        .map(|name| {
+                            let name = name.trim();
+                            FAMILIES
+                                .into_iter()
+                                .find(|family| family.to_string().eq_ignore_ascii_case(name))
+                                .ok_or_else(|| format!("unknown family '{}'", name))
+                        })
+                        .collect::<Result<Vec<Family>, String>>()?;
+                    capabilities.families = Some(families);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(capabilities)
+    }
+
+    /// Refuses sessions using commands or families the firmware does not support, or sending lines
+    /// longer than it accepts.
+    pub fn check(&self, requirements: &Requirements, longest_line: usize) -> Result<(), String> {
+        if let Some(commands) = &self.commands {
+            let missing: Vec<String> = requirements
+                .commands
+                .iter()
+                .filter(|command| !commands.contains(command))
+                .map(|command| command.to_string())
+                .collect();
+            if !missing.is_empty() {
+                return Err(format!(
+                    "firmware {} does not support command(s) {}",
+                    self.version,
+                    missing.join(", ")
+                ));
+            }
+        }
+
+        if let (Some(families), Some(family)) = (&self.families, requirements.family) {
+            if !families.contains(&family) {
+                return Err(format!(
+                    "firmware {} does not support {} devices",
+                    self.version, family
+                ));
+            }
+        }
+
+        if let Some(max_line_length) = self.max_line_length {
+            if longest_line > max_line_length {
+                return Err(format!(
+                    "firmware {} accepts lines of up to {} characters, the file has lines of {}",
+                    self.version, max_line_length, longest_line
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version: {}", self.version)?;
+
+        match &self.commands {
+            Some(commands) => writeln!(f, "commands: {}", commands.iter().collect::<String>())?,
+            None => writeln!(f, "commands: unknown")?,
+        }
+        match self.max_line_length {
+            Some(length) => writeln!(f, "max line length: {}", length)?,
+            None => writeln!(f, "max line length: unknown")?,
+        }
+        match &self.families {
+            Some(families) => {
+                let names: Vec<String> = families.iter().map(|f| f.to_string()).collect();
+                write!(f, "families: {}", names.join(", "))
+            }
+            None => write!(f, "families: unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::device::Family;
+    use crate::programmer::capabilities::{Capabilities, Requirements};
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn given_identify_answer_should_parse_all_fields() {
+        let capabilities = Capabilities::parse(&lines(&[
+            "version=2.1",
+            "commands=PDEI",
+            "max-line=64",
+            "families=PIC12, pic16",
+            "debug=on",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                version: String::from("2.1"),
+                commands: Some(vec!['P', 'D', 'E', 'I']),
+                max_line_length: Some(64),
+                families: Some(vec![Family::Pic12, Family::Pic16]),
+            }
+        );
+    }
+
+    #[test]
+    fn given_partial_answer_should_not_check_unknown_fields() {
+        let capabilities = Capabilities::parse(&lines(&["version=1.0"])).unwrap();
+        let requirements = Requirements {
+            commands: vec!['E'],
+            family: Some(Family::Pic18),
+        };
+
+        assert!(capabilities.check(&requirements, 200).is_ok());
+    }
+
+    #[test]
+    fn given_unsupported_command_family_or_line_length_should_refuse() {
+        let capabilities = Capabilities::parse(&lines(&[
+            "version=1.2",
+            "commands=PD",
+            "max-line=44",
+            "families=PIC16",
+        ]))
+        .unwrap();
+        let program = Requirements {
+            commands: vec!['P'],
+            family: Some(Family::Pic16),
+        };
+        let erase = Requirements {
+            commands: vec!['E'],
+            family: None,
+        };
+        let pic18 = Requirements {
+            commands: vec!['P'],
+            family: Some(Family::Pic18),
+        };
+
+        assert!(capabilities.check(&program, 43).is_ok());
+        assert!(capabilities.check(&program, 45).is_err());
+        assert!(capabilities.check(&erase, 0).is_err());
+        assert!(capabilities.check(&pic18, 0).is_err());
+    }
+
+    #[test]
+    fn given_invalid_values_should_fail() {
+        assert!(Capabilities::parse(&lines(&["max-line=many"])).is_err());
+        assert!(Capabilities::parse(&lines(&["families=AVR"])).is_err());
+    }
+}
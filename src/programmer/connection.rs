@@ -0,0 +1,77 @@
+use crate::info;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::port_selector::resolve_port;
+use crate::programmer::reset::Reset;
+use crate::programmer::serial_programmer::SerialProgrammer;
+use crate::programmer::serial_reader::SerialReader;
+use crate::programmer::serial_writer::SerialWriter;
+use crate::programmer::timeouts::Timeouts;
+use serialport::SerialPort;
+use std::time::Duration;
+
+/// Serial port settings used to reach the Arduino running the programmer. `timeout` bounds a single
+/// read or write on the port, the protocol deadlines are in `timeouts`. With `identify` every session
+/// starts with a handshake checking the firmware capabilities. `reset` brings the programmer to
+/// send its ready message once the port is open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub port_name: String,
+    pub baud_rate: u32,
+    pub timeout: u64,
+    pub timeouts: Timeouts,
+    pub identify: bool,
+    pub reset: Reset,
+}
+
+impl Connection {
+    pub fn new(port_name: &str, baud_rate: u32, timeout: u64) -> Connection {
+        Connection {
+            port_name: port_name.to_string(),
+            baud_rate,
+            timeout,
+            timeouts: Timeouts::default(),
+            identify: false,
+            reset: Reset::default(),
+        }
+    }
+
+    /// Opens the port, detecting it first when the port name is `auto` or looking it up for the
+    /// `usb:VID:PID` and `serial:SERIAL_NUMBER` selectors, and resets the programmer.
+    pub fn open(&self) -> Result<Box<dyn SerialPort>, ProgrammerError> {
+        let port_name = resolve_port(self)?;
+
+        let mut port = serialport::new(&port_name, self.baud_rate)
+            .timeout(Duration::from_millis(self.timeout))
+            .open()
+            .map_err(|error| ProgrammerError::PortOpen {
+                port_name: port_name.clone(),
+                error,
+            })?;
+
+        info!(
+            "created connection data on {} at {} baud:",
+            &port_name, &self.baud_rate
+        );
+        self.reset.apply(&mut port, &mut SerialWriter::new())?;
+
+        Ok(port)
+    }
+
+    /// Programmer using the deadlines of this connection, `requirements` are only checked with `identify`.
+    pub fn programmer(
+        &self,
+        requirements: Requirements,
+    ) -> SerialProgrammer<SerialReader, SerialWriter> {
+        let serial_reader = SerialReader::new(vec![0; 1000]);
+        let serial_writer = SerialWriter::new();
+        let serial_programmer =
+            SerialProgrammer::new(serial_reader, serial_writer).with_timeouts(self.timeouts);
+
+        if self.identify {
+            serial_programmer.with_handshake(requirements)
+        } else {
+            serial_programmer
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::response::{Response, ResponseParser};
+use crate::programmer::serial_reader::{ReadSerial, SerialReader};
+use crate::programmer::serial_writer::SerialWriter;
+use crate::{info, warn};
+use serialport::{SerialPort, SerialPortType};
+use std::time::{Duration, Instant};
+
+/// Port name asking for the programmer port to be detected.
+pub const AUTO_PORT: &str = "auto";
+
+/// Opens the candidate ports one by one and returns the first one sending the ready message within
+/// the detect timeout, which is shorter than the ready deadline of a session so silent ports are
+/// skipped quickly. Arduinos show up as USB ports, so other ports are only probed when there are
+/// no USB ports at all.
+pub fn detect_port(connection: &Connection) -> Result<String, ProgrammerError> {
+    let ports = serialport::available_ports().map_err(|error| ProgrammerError::PortOpen {
+        port_name: AUTO_PORT.to_string(),
+        error,
+    })?;
+
+    let usb_ports: Vec<String> = ports
+        .iter()
+        .filter(|port| matches!(port.port_type, SerialPortType::UsbPort(_)))
+        .map(|port| port.port_name.clone())
+        .collect();
+    let candidates = if usb_ports.is_empty() {
+        ports.into_iter().map(|port| port.port_name).collect()
+    } else {
+        usb_ports
+    };
+
+    for port_name in &candidates {
+        info!("probing {} for the programmer...", port_name);
+        let port = serialport::new(port_name, connection.baud_rate)
+            .timeout(Duration::from_millis(connection.timeout))
+            .open();
+        let mut port = match port {
+            Ok(port) => port,
+            Err(e) => {
+                warn!("skipping {}: {}", port_name, e);
+                continue;
+            }
+        };
+
+        let mut reader = SerialReader::new(vec![0; 1000]);
+        let detected = connection
+            .reset
+            .apply(&mut port, &mut SerialWriter::new())
+            .and_then(|_| sends_ready(&mut reader, &mut port, connection.timeouts.detect));
+        match detected {
+            Ok(true) => {
+                info!("programmer found on {}", port_name);
+                return Ok(port_name.clone());
+            }
+            Ok(false) => info!("no programmer on {}", port_name),
+            Err(e) => warn!("skipping {}: {}", port_name, e),
+        }
+    }
+
+    Err(ProgrammerError::NoProgrammerFound { candidates })
+}
+
+fn sends_ready<R: ReadSerial>(
+    reader: &mut R,
+    port: &mut Box<dyn SerialPort>,
+    window: Duration,
+) -> Result<bool, ProgrammerError> {
+    let started = Instant::now();
+    let mut parser = ResponseParser::default();
+
+    while started.elapsed() < window {
+        let mut received_data = String::new();
+        reader.read(port, &mut received_data)?;
+        parser.push(&received_data);
+
+        while let Some(response) = parser.next_response() {
+            if response == Response::Ready {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::programmer::detect::sends_ready;
+    use crate::programmer::error::ProgrammerError;
+    use crate::programmer::serial_reader::ReadSerial;
+    use crate::programmer::test_serial_port::TestSerialPort;
+    use serialport::SerialPort;
+    use std::time::Duration;
+
+    struct ReaderTest {
+        data: Vec<&'static str>,
+    }
+
+    impl ReadSerial for ReaderTest {
+        fn read(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            received_data: &mut String,
+        ) -> Result<(), ProgrammerError> {
+            if !self.data.is_empty() {
+                received_data.push_str(self.data.remove(0));
+            }
+            Ok(())
+        }
+
+        fn data_follows(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            _window: Duration,
+        ) -> Result<bool, ProgrammerError> {
+            Ok(!self.data.is_empty())
+        }
+    }
+
+    #[test]
+    fn given_port_sending_ready_message_should_be_detected() {
+        let mut reader = ReaderTest {
+            data: vec!["\x00boot\r\n", "Programmer ", "ready!\r\n"],
+        };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+
+        let detected = sends_ready(&mut reader, &mut port, Duration::from_secs(5));
+
+        assert!(detected.unwrap());
+    }
+
+    #[test]
+    fn given_port_sending_other_data_should_not_be_detected() {
+        let mut reader = ReaderTest {
+            data: vec!["GPS $GPGGA,123519\r\n"],
+        };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+
+        let detected = sends_ready(&mut reader, &mut port, Duration::from_millis(10));
+
+        assert!(!detected.unwrap());
+    }
+}
@@ -0,0 +1,22 @@
+use crate::info;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+
+use crate::programmer::serial_programmer::EraseMode;
+
+pub fn erase_pic(connection: &Connection, mode: EraseMode) -> Result<(), ProgrammerError> {
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "erasing using Arduino at {0} with baud rate {1}...",
+        port_name, baud_rate
+    );
+
+    let mut port = connection.open()?;
+    let requirements = Requirements {
+        commands: vec!['E'],
+        family: None,
+    };
+    connection.programmer(requirements).erase(&mut port, mode)
+}
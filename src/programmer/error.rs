@@ -0,0 +1,229 @@
+use crate::hex::hex_file::HexError;
+use crate::programmer::response::ErrorCode;
+use crate::programmer::timeouts::Phase;
+use std::time::Duration;
+use std::{fmt, io};
+
+/// Failures of the commands, each class has its own process exit code. Codes start at 3, 1 is left
+/// for other failures and 2 is used by clap for usage errors.
+#[derive(Debug)]
+pub enum ProgrammerError {
+    PortOpen {
+        port_name: String,
+        error: serialport::Error,
+    },
+    NoProgrammerFound {
+        candidates: Vec<String>,
+    },
+    PortNotFound {
+        selector: String,
+        matches: Vec<String>,
+    },
+    Serial(io::Error),
+    File(String),
+    Protocol(String),
+    Incompatible(String),
+    Programmer {
+        code: ErrorCode,
+        phase: Phase,
+    },
+    ResendLimit {
+        line: usize,
+        record: String,
+        line_resends: u32,
+        session_resends: u32,
+        session: bool,
+    },
+    Timeout {
+        phase: Phase,
+        limit: Duration,
+        overall: bool,
+        received: String,
+    },
+    Verification(String),
+    Unsupported(String),
+}
+
+impl ProgrammerError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ProgrammerError::PortOpen { .. }
+            | ProgrammerError::NoProgrammerFound { .. }
+            | ProgrammerError::PortNotFound { .. }
+            | ProgrammerError::Serial(_) => 3,
+            ProgrammerError::File(_) => 4,
+            ProgrammerError::Protocol(_)
+            | ProgrammerError::Incompatible(_)
+            | ProgrammerError::ResendLimit { .. } => 5,
+            ProgrammerError::Timeout { .. } => 6,
+            ProgrammerError::Verification(_) => 7,
+            ProgrammerError::Unsupported(_) => 1,
+            ProgrammerError::Programmer { code, .. } => match code {
+                ErrorCode::AddressOutOfRange => 4,
+                ErrorCode::WriteTimeout => 6,
+                ErrorCode::VerifyFailed => 7,
+                ErrorCode::Unknown(_) => 5,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ProgrammerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgrammerError::PortOpen { port_name, error } => {
+                write!(f, "Failed to open \"{}\". Error: {}", port_name, error)
+            }
+            ProgrammerError::NoProgrammerFound { candidates } if candidates.is_empty() => {
+                write!(f, "no serial ports found to detect the programmer on")
+            }
+            ProgrammerError::NoProgrammerFound { candidates } => {
+                write!(f, "no programmer answered on {}", candidates.join(", "))
+            }
+            ProgrammerError::PortNotFound { selector, matches } if matches.is_empty() => {
+                write!(f, "no serial port matches {}", selector)
+            }
+            ProgrammerError::PortNotFound { selector, matches } => write!(
+                f,
+                "{} matches several ports ({}), select one by serial number",
+                selector,
+                matches.join(", ")
+            ),
+            ProgrammerError::Serial(error) => write!(f, "serial port error: {}", error),
+            ProgrammerError::File(message) => write!(f, "{}", message),
+            ProgrammerError::Protocol(message) => write!(f, "protocol error: {}", message),
+            ProgrammerError::Incompatible(message) => {
+                write!(f, "incompatible firmware: {}", message)
+            }
+            ProgrammerError::Programmer { code, phase } => write!(
+                f,
+                "programmer reported E{} ({}) while waiting for {}",
+                code.code(),
+                code,
+                phase
+            ),
+            ProgrammerError::ResendLimit {
+                line,
+                record,
+                line_resends,
+                session_resends,
+                session,
+            } => {
+                if *session {
+                    write!(f, "session resend limit of {} reached", session_resends)?;
+                } else {
+                    write!(f, "line resend limit of {} reached", line_resends)?;
+                }
+                write!(
+                    f,
+                    ", record {} (line {}) kept failing after {} resend(s), {} in the session",
+                    record, line, line_resends, session_resends
+                )
+            }
+            ProgrammerError::Timeout {
+                phase: phase @ Phase::Write { .. },
+                limit,
+                ..
+            } => write!(
+                f,
+                "timeout of {} ms expired waiting for {}",
+                limit.as_millis(),
+                phase
+            ),
+            ProgrammerError::Timeout {
+                phase,
+                limit,
+                overall,
+                received,
+            } => write!(
+                f,
+                "{}timeout of {} ms expired waiting for {}, last bytes received: {:?}",
+                if *overall { "overall " } else { "" },
+                limit.as_millis(),
+                phase,
+                received
+            ),
+            ProgrammerError::Verification(message) => write!(f, "{}", message),
+            ProgrammerError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProgrammerError {}
+
+impl From<Vec<HexError>> for ProgrammerError {
+    fn from(errors: Vec<HexError>) -> ProgrammerError {
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        ProgrammerError::File(messages.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::hex_file::HexFile;
+    use crate::programmer::error::ProgrammerError;
+    use crate::programmer::timeouts::Phase;
+    use std::time::Duration;
+
+    #[test]
+    fn given_failure_classes_should_use_distinct_exit_codes() {
+        let errors = [
+            ProgrammerError::File(String::new()),
+            ProgrammerError::Protocol(String::new()),
+            ProgrammerError::Timeout {
+                phase: Phase::Ready,
+                limit: Duration::ZERO,
+                overall: false,
+                received: String::new(),
+            },
+            ProgrammerError::Verification(String::new()),
+        ];
+
+        let codes: Vec<i32> = errors.iter().map(|error| error.exit_code()).collect();
+
+        assert_eq!(codes, vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn given_timeout_should_name_phase_and_last_bytes() {
+        let error = ProgrammerError::Timeout {
+            phase: Phase::Ack { line: 3 },
+            limit: Duration::from_millis(500),
+            overall: false,
+            received: String::from("xx\n"),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "timeout of 500 ms expired waiting for the acknowledgement of line 3, last bytes received: \"xx\\n\""
+        );
+    }
+
+    #[test]
+    fn given_write_timeout_should_name_bytes_written() {
+        let error = ProgrammerError::Timeout {
+            phase: Phase::Write {
+                written: 3,
+                total: 15,
+            },
+            limit: Duration::from_millis(100),
+            overall: false,
+            received: String::new(),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "timeout of 100 ms expired waiting for the serial port write (3 of 15 bytes written)"
+        );
+    }
+
+    #[test]
+    fn given_hex_errors_should_report_one_per_line() {
+        let errors = HexFile::parse("test.hex", ":00000001FE\nxyz\n").unwrap_err();
+
+        let error = ProgrammerError::from(errors);
+
+        assert_eq!(error.exit_code(), 4);
+        assert_eq!(error.to_string().lines().count(), 2);
+    }
+}
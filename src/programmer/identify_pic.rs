@@ -0,0 +1,24 @@
+use crate::info;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+
+pub fn identify_pic(connection: &Connection) -> Result<(), ProgrammerError> {
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "identifying Arduino at {0} with baud rate {1}...",
+        port_name, baud_rate
+    );
+
+    let mut port = connection.open()?;
+    let requirements = Requirements {
+        commands: vec![],
+        family: None,
+    };
+    let capabilities = connection.programmer(requirements).identify(&mut port)?;
+
+    println!("{}", capabilities);
+
+    Ok(())
+}
@@ -1,7 +1,21 @@
-mod file_reader;
+pub mod blank_check_pic;
+pub mod capabilities;
+pub mod connection;
+pub mod detect;
+pub mod erase_pic;
+pub mod error;
+pub mod identify_pic;
+pub mod port_selector;
 pub mod program_pic;
+pub mod progress;
 pub mod read_pic;
-mod serial_programmer;
-mod serial_reader;
-mod serial_writer;
+pub mod resend_policy;
+pub mod reset;
+pub mod response;
+pub mod serial_programmer;
+pub mod serial_reader;
+pub mod serial_writer;
+#[cfg(test)]
 mod test_serial_port;
+pub mod timeouts;
+pub mod verify_pic;
@@ -0,0 +1,203 @@
+use crate::info;
+use crate::list_ports::{parse_usb_id, PortDescription};
+use crate::programmer::connection::Connection;
+use crate::programmer::detect::{detect_port, AUTO_PORT};
+use crate::programmer::error::ProgrammerError;
+use serialport::ErrorKind;
+use std::fmt;
+
+const USB_PREFIX: &str = "usb:";
+const SERIAL_PREFIX: &str = "serial:";
+
+/// The ways to name the programmer port: `auto`, `usb:VID:PID`, `serial:SERIAL_NUMBER` or a plain
+/// port name. The USB selectors survive replugging, the port name given by the OS does not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortSelector {
+    Auto,
+    Usb { vid: u16, pid: u16 },
+    SerialNumber(String),
+    Name(String),
+}
+
+impl PortSelector {
+    pub fn parse(value: &str) -> Result<PortSelector, String> {
+        if value.eq_ignore_ascii_case(AUTO_PORT) {
+            return Ok(PortSelector::Auto);
+        }
+
+        if let Some(ids) = value.strip_prefix(USB_PREFIX) {
+            let (vid, pid) = ids.split_once(':').ok_or_else(|| {
+                format!("expected usb:VID:PID (e.g., usb:2341:0043), got {}", value)
+            })?;
+            return Ok(PortSelector::Usb {
+                vid: parse_usb_id(vid)?,
+                pid: parse_usb_id(pid)?,
+            });
+        }
+
+        if let Some(serial_number) = value.strip_prefix(SERIAL_PREFIX) {
+            if serial_number.is_empty() {
+                return Err(String::from(
+                    "expected serial:SERIAL_NUMBER (e.g., serial:85739313)",
+                ));
+            }
+            return Ok(PortSelector::SerialNumber(serial_number.to_string()));
+        }
+
+        Ok(PortSelector::Name(value.to_string()))
+    }
+
+    pub fn matches(&self, port: &PortDescription) -> bool {
+        match (self, &port.usb) {
+            (PortSelector::Usb { vid, pid }, Some(usb)) => usb.vid == *vid && usb.pid == *pid,
+            (PortSelector::SerialNumber(serial_number), Some(usb)) => usb
+                .serial_number
+                .as_ref()
+                .is_some_and(|port_serial| port_serial.eq_ignore_ascii_case(serial_number)),
+            (PortSelector::Name(name), _) => port.name == *name,
+            _ => false,
+        }
+    }
+
+    /// Returns the name of the only port matching the selector.
+    pub fn select(&self, ports: &[PortDescription]) -> Result<String, ProgrammerError> {
+        let matches: Vec<String> = ports
+            .iter()
+            .filter(|port| self.matches(port))
+            .map(|port| port.name.clone())
+            .collect();
+
+        match matches.as_slice() {
+            [port_name] => Ok(port_name.clone()),
+            _ => Err(ProgrammerError::PortNotFound {
+                selector: self.to_string(),
+                matches,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for PortSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortSelector::Auto => write!(f, "{}", AUTO_PORT),
+            PortSelector::Usb { vid, pid } => write!(f, "{}{:04x}:{:04x}", USB_PREFIX, vid, pid),
+            PortSelector::SerialNumber(serial_number) => {
+                write!(f, "{}{}", SERIAL_PREFIX, serial_number)
+            }
+            PortSelector::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Checks the port selector given on the command line, keeping it as is.
+pub fn parse_port_name(value: &str) -> Result<String, String> {
+    PortSelector::parse(value).map(|_| value.to_string())
+}
+
+/// Resolves the port name of the connection to the path of the port to open.
+pub fn resolve_port(connection: &Connection) -> Result<String, ProgrammerError> {
+    let selector = PortSelector::parse(&connection.port_name).map_err(|message| {
+        ProgrammerError::PortOpen {
+            port_name: connection.port_name.clone(),
+            error: serialport::Error::new(ErrorKind::InvalidInput, message),
+        }
+    })?;
+
+    match selector {
+        PortSelector::Auto => detect_port(connection),
+        PortSelector::Name(port_name) => Ok(port_name),
+        selector => {
+            let ports =
+                serialport::available_ports().map_err(|error| ProgrammerError::PortOpen {
+                    port_name: connection.port_name.clone(),
+                    error,
+                })?;
+            let ports: Vec<PortDescription> =
+                ports.into_iter().map(PortDescription::from).collect();
+
+            let port_name = selector.select(&ports)?;
+            info!("{} resolved to {}", selector, port_name);
+            Ok(port_name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::list_ports::{PortDescription, UsbDescription};
+    use crate::programmer::error::ProgrammerError;
+    use crate::programmer::port_selector::PortSelector;
+
+    fn usb_port(name: &str, pid: u16, serial_number: &str) -> PortDescription {
+        PortDescription {
+            name: name.to_string(),
+            kind: "USB",
+            usb: Some(UsbDescription {
+                vid: 0x2341,
+                pid,
+                manufacturer: None,
+                product: None,
+                serial_number: Some(serial_number.to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn given_selectors_should_parse() {
+        assert_eq!(PortSelector::parse("AUTO"), Ok(PortSelector::Auto));
+        assert_eq!(
+            PortSelector::parse("usb:2341:0043"),
+            Ok(PortSelector::Usb {
+                vid: 0x2341,
+                pid: 0x0043
+            })
+        );
+        assert_eq!(
+            PortSelector::parse("serial:85739313"),
+            Ok(PortSelector::SerialNumber(String::from("85739313")))
+        );
+        assert_eq!(
+            PortSelector::parse("COM3"),
+            Ok(PortSelector::Name(String::from("COM3")))
+        );
+        assert!(PortSelector::parse("usb:2341").is_err());
+        assert!(PortSelector::parse("serial:").is_err());
+    }
+
+    #[test]
+    fn given_usb_selectors_should_select_matching_port() {
+        let ports = [
+            usb_port("/dev/ttyACM0", 0x0043, "85739313"),
+            usb_port("/dev/ttyACM1", 0x0042, "95530343"),
+        ];
+
+        let by_ids = PortSelector::parse("usb:2341:0042").unwrap().select(&ports);
+        let by_serial = PortSelector::parse("serial:85739313")
+            .unwrap()
+            .select(&ports);
+
+        assert_eq!(by_ids.unwrap(), "/dev/ttyACM1");
+        assert_eq!(by_serial.unwrap(), "/dev/ttyACM0");
+    }
+
+    #[test]
+    fn given_ambiguous_or_missing_port_should_fail() {
+        let ports = [
+            usb_port("/dev/ttyACM0", 0x0043, "85739313"),
+            usb_port("/dev/ttyACM1", 0x0043, "95530343"),
+        ];
+
+        let ambiguous = PortSelector::parse("usb:2341:0043").unwrap().select(&ports);
+        let missing = PortSelector::parse("serial:1234").unwrap().select(&ports);
+
+        assert!(matches!(
+            ambiguous,
+            Err(ProgrammerError::PortNotFound { matches, .. }) if matches.len() == 2
+        ));
+        assert!(matches!(
+            missing,
+            Err(ProgrammerError::PortNotFound { matches, .. }) if matches.is_empty()
+        ));
+    }
+}
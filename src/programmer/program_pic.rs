@@ -1,50 +1,107 @@
-use crate::programmer::file_reader::get_lines;
-use serialport::SerialPort;
-use std::fs::File;
-use std::io;
-use std::io::BufReader;
+use crate::config_word::{apply_overrides, print_config, ConfigOverride};
+use crate::device::Device;
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::MemoryImage;
+use crate::hex::validation::{validate, ValidationIssue};
+use crate::info;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
 
-use crate::programmer::serial_programmer::SerialProgrammer;
-use crate::programmer::serial_reader::SerialReader;
-use crate::programmer::serial_writer::SerialWriter;
-use std::time::Duration;
+use crate::programmer::resend_policy::ResendPolicy;
+use crate::programmer::verify_pic::verify;
 
 pub fn program_pic(
     input_file_path: &String,
-    port_name: &String,
-    baud_rate: u32,
-    timeout: u64,
-    verbose: bool,
-) {
-    println!(
-        "[CLI] sending file '{0}' to Arduino connected at {1} with baud rate {2}...",
+    connection: &Connection,
+    resend_policy: ResendPolicy,
+    device: Option<&'static Device>,
+    config_overrides: &[ConfigOverride],
+    verify_after_programming: bool,
+) -> Result<(), ProgrammerError> {
+    info!("reading file {}", input_file_path);
+    let mut hex_file = HexFile::read(input_file_path)?;
+
+    if let Some(device) = device {
+        check_device_fits(input_file_path, &hex_file, device)?;
+        patch_config(&mut hex_file, device, config_overrides)?;
+        print_config(device, &MemoryImage::from_hex_file(&hex_file));
+    }
+
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "sending file '{0}' to Arduino connected at {1} with baud rate {2}...",
         input_file_path, port_name, baud_rate
     );
 
-    let port = serialport::new(port_name, baud_rate)
-        .timeout(Duration::from_millis(timeout))
-        .open();
-
-    match port {
-        Ok(mut port) => {
-            println!(
-                "[CLI] created connection data on {} at {} baud:",
-                &port_name, &baud_rate
-            );
-            let lines = get_lines(input_file_path);
-            program(lines, &mut port, verbose);
-        }
-        Err(e) => {
-            eprintln!("[CLI] Failed to open \"{}\". Error: {}", port_name, e);
-            std::process::exit(1);
-        }
+    let mut port = connection.open()?;
+    let mut commands = vec!['P'];
+    if verify_after_programming {
+        commands.push('D');
+    }
+    let requirements = Requirements {
+        commands,
+        family: device.map(|device| device.family),
+    };
+    let mut serial_programmer = connection
+        .programmer(requirements)
+        .with_resend_policy(resend_policy);
+
+    serial_programmer.program(&mut port, &hex_file)?;
+
+    if verify_after_programming {
+        let lines = serial_programmer.read_back(&mut port)?;
+        verify(&MemoryImage::from_hex_file(&hex_file), &lines)?;
+    }
+
+    Ok(())
+}
+
+fn check_device_fits(
+    input_file_path: &String,
+    hex_file: &HexFile,
+    device: &Device,
+) -> Result<(), ProgrammerError> {
+    info!("target device {}", device);
+
+    let issues: Vec<ValidationIssue> = validate(hex_file, &device.writable_ranges())
+        .into_iter()
+        .filter(|issue| matches!(issue, ValidationIssue::AddressOutOfRange { .. }))
+        .collect();
+    if issues.is_empty() {
+        return Ok(());
     }
+
+    let mut messages: Vec<String> = issues
+        .iter()
+        .map(|issue| format!("{}: {}", input_file_path, issue))
+        .collect();
+    messages.push(format!("file does not fit in {}", device.name));
+    Err(ProgrammerError::File(messages.join("\n")))
 }
 
-fn program(lines: io::Lines<BufReader<File>>, port: &mut Box<dyn SerialPort>, verbose: bool) {
-    let serial_reader = SerialReader::new(vec![0; 1000], verbose);
-    let serial_writer = SerialWriter::new(verbose);
-    let mut serial_programmer = SerialProgrammer::new(serial_reader, serial_writer, verbose);
+fn patch_config(
+    hex_file: &mut HexFile,
+    device: &Device,
+    config_overrides: &[ConfigOverride],
+) -> Result<(), ProgrammerError> {
+    if config_overrides.is_empty() {
+        return Ok(());
+    }
+
+    let patched =
+        apply_overrides(device, hex_file, config_overrides).map_err(ProgrammerError::File)?;
+    for word in patched {
+        let previous = match word.previous {
+            Some(previous) => format!("0x{:04X}", previous),
+            None => String::from("missing"),
+        };
+        info!(
+            "config 0x{:06X} patched from {} to 0x{:04X}",
+            word.address, previous, word.word
+        );
+    }
 
-    serial_programmer.program(port, lines);
+    Ok(())
 }
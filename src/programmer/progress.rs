@@ -0,0 +1,217 @@
+use crate::hex::hex_file::HexFile;
+use crate::hex::record::RecordType;
+use crate::info;
+use crate::log;
+use crate::log::Level;
+use std::io;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+const REPORT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Bar,
+    Lines,
+    Hidden,
+}
+
+/// Progress of a programming session. On a terminal a bar is redrawn after every record, otherwise
+/// a plain line is printed every few seconds. Bytes are the data bytes of the records, not the
+/// characters sent for them. A bar left unfinished, e.g. by an error, is ended with a line break
+/// when dropped.
+#[derive(Debug)]
+pub struct Progress {
+    record_bytes: Vec<usize>,
+    total_records: usize,
+    total_bytes: usize,
+    records: usize,
+    bytes: usize,
+    resends: u32,
+    started: Instant,
+    last_report: Instant,
+    output: Output,
+    finished: bool,
+}
+
+impl Progress {
+    /// Counts the records sent for the file, which stops after the end of file record.
+    pub fn new(hex_file: &HexFile, enabled: bool) -> Progress {
+        let mut record_bytes = vec![];
+        for line in &hex_file.lines {
+            record_bytes.push(line.record.data.len());
+            if line.record.record_type == RecordType::EndOfFile {
+                break;
+            }
+        }
+        let total_records = record_bytes.len();
+        let total_bytes = record_bytes.iter().sum();
+
+        let enabled =
+            enabled && log::shown_on_console(Level::Info) && !log::shown_on_console(Level::Debug);
+        let output = match (enabled, io::stdout().is_terminal()) {
+            (false, _) => Output::Hidden,
+            (true, true) => Output::Bar,
+            (true, false) => Output::Lines,
+        };
+
+        Progress {
+            record_bytes,
+            total_records,
+            total_bytes,
+            records: 0,
+            bytes: 0,
+            resends: 0,
+            started: Instant::now(),
+            last_report: Instant::now(),
+            output,
+            finished: false,
+        }
+    }
+
+    /// Counts the next record of the file as acknowledged.
+    pub fn record_sent(&mut self) {
+        self.bytes += self.record_bytes.get(self.records).copied().unwrap_or(0);
+        self.records += 1;
+        self.report(false);
+    }
+
+    pub fn resent(&mut self) {
+        self.resends += 1;
+    }
+
+    /// Clears the bar so other output starts on an empty line, the next record redraws it.
+    pub fn clear(&self) {
+        if self.output == Output::Bar {
+            print!("\r\x1b[2K");
+            let _ = io::stdout().flush();
+        }
+    }
+
+    pub fn finish(&mut self) {
+        if !self.finished {
+            self.report(true);
+            self.end_bar();
+        }
+    }
+
+    fn end_bar(&mut self) {
+        if self.output == Output::Bar && !self.finished {
+            println!();
+        }
+        self.finished = true;
+    }
+
+    fn report(&mut self, last: bool) {
+        let elapsed = self.started.elapsed();
+        match self.output {
+            Output::Bar => {
+                print!("\r\x1b[2K[CLI] {} {}", self.bar(), self.render(elapsed));
+                let _ = io::stdout().flush();
+            }
+            Output::Lines if last || self.last_report.elapsed() >= REPORT_INTERVAL => {
+                info!("programmed {}", self.render(elapsed));
+                self.last_report = Instant::now();
+            }
+            _ => {}
+        }
+    }
+
+    fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.bytes as f64 / self.total_bytes as f64
+        }
+    }
+
+    fn bar(&self) -> String {
+        let filled = ((self.fraction() * BAR_WIDTH as f64) as usize).min(BAR_WIDTH);
+        format!("[{}{}]", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))
+    }
+
+    fn render(&self, elapsed: Duration) -> String {
+        let seconds = elapsed.as_secs_f64();
+        let throughput = if seconds > 0.0 {
+            self.bytes as f64 / seconds
+        } else {
+            0.0
+        };
+        let eta = if throughput > 0.0 {
+            let remaining = self.total_bytes.saturating_sub(self.bytes) as f64 / throughput;
+            format_duration(Duration::from_secs_f64(remaining))
+        } else {
+            String::from("-")
+        };
+
+        format!(
+            "{}/{} records ({:.0}%), {}/{}, {} resends, {}/s, ETA {}",
+            self.records,
+            self.total_records,
+            self.fraction() * 100.0,
+            format_bytes(self.bytes as f64),
+            format_bytes(self.total_bytes as f64),
+            self.resends,
+            format_bytes(throughput),
+            eta
+        )
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.records > 0 {
+            self.end_bar();
+        }
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    if bytes < 1024.0 {
+        format!("{:.0} B", bytes)
+    } else {
+        format!("{:.1} KB", bytes / 1024.0)
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hex::hex_file::HexFile;
+    use crate::programmer::progress::Progress;
+    use std::time::Duration;
+
+    #[test]
+    fn given_file_should_count_records_up_to_end_of_file() {
+        let hex_file = HexFile::read("src/programmer/test-files/eof-at-the-beginning.hex").unwrap();
+
+        let progress = Progress::new(&hex_file, false);
+
+        assert_eq!(progress.total_records, 1);
+        assert_eq!(progress.total_bytes, 0);
+    }
+
+    #[test]
+    fn given_first_record_sent_should_render_throughput_and_eta() {
+        let hex_file =
+            HexFile::read("src/programmer/test-files/multiple-instructions.hex").unwrap();
+        let mut progress = Progress::new(&hex_file, false);
+
+        progress.record_sent();
+        progress.resent();
+
+        assert_eq!(
+            progress.render(Duration::from_secs(2)),
+            "1/3 records (50%), 2 B/4 B, 1 resends, 1 B/s, ETA 0:02"
+        );
+        assert_eq!(
+            progress.bar(),
+            format!("[{}{}]", "#".repeat(15), "-".repeat(15))
+        );
+    }
+}
@@ -1,39 +1,103 @@
-use serialport::SerialPort;
+use crate::config_word::print_config;
+use crate::device::Device;
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::MemoryImage;
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+use crate::{info, warn};
 
-use crate::programmer::serial_programmer::SerialProgrammer;
-use crate::programmer::serial_reader::SerialReader;
-use crate::programmer::serial_writer::SerialWriter;
-use std::time::Duration;
-
-pub fn read_pic(port_name: &String, baud_rate: u32, timeout: u64, verbose: bool) {
-    println!(
-        "[CLI] will receive from Arduino at {0} with baud rate {1}...",
+pub fn read_pic(
+    connection: &Connection,
+    output_file_path: &Option<String>,
+    device: Option<&'static Device>,
+) -> Result<(), ProgrammerError> {
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "will receive from Arduino at {0} with baud rate {1}...",
         port_name, baud_rate
     );
 
-    let port = serialport::new(port_name, baud_rate)
-        .timeout(Duration::from_millis(timeout))
-        .open();
+    let mut port = connection.open()?;
+    let requirements = Requirements {
+        commands: vec!['D'],
+        family: device.map(|device| device.family),
+    };
+    let lines = connection.programmer(requirements).read(&mut port)?;
 
-    match port {
-        Ok(mut port) => {
-            println!(
-                "[CLI] created connection data on {} at {} baud:",
-                &port_name, &baud_rate
-            );
-            read(&mut port, verbose);
+    if device.is_none() && output_file_path.is_none() {
+        return Ok(());
+    }
+
+    let image = image_from_lines(&lines)?;
+    if let Some(device) = device {
+        check_read_back(&image, device);
+    }
+    if let Some(output_file_path) = output_file_path {
+        save(&image, output_file_path)?;
+    }
+
+    Ok(())
+}
+
+/// Builds a memory image out of the records sent by the programmer, other lines are ignored.
+pub fn image_from_lines(lines: &[String]) -> Result<MemoryImage, ProgrammerError> {
+    let records: Vec<&str> = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| line.starts_with(':'))
+        .collect();
+
+    match HexFile::parse("<programmer>", &records.join("\n")) {
+        Ok(hex_file) => Ok(MemoryImage::from_hex_file(&hex_file)),
+        Err(errors) => {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Err(ProgrammerError::Protocol(messages.join("\n")))
         }
-        Err(e) => {
-            eprintln!("[CLI] Failed to open \"{}\". Error: {}", port_name, e);
-            std::process::exit(1);
+    }
+}
+
+/// Warns about a read-back that does not look like it came from the selected device
+/// and decodes its configuration words.
+fn check_read_back(image: &MemoryImage, device: &Device) {
+    info!("target device {}", device);
+
+    if let Some(address) = image
+        .bytes
+        .keys()
+        .find(|address| device.region_of(**address).is_none())
+    {
+        warn!(
+            "read-back contains address 0x{:06X} outside of {}",
+            address, device.name
+        );
+    }
+
+    let device_id = device
+        .device_id_address
+        .and_then(|address| image.word(address));
+    if let Some(device_id) = device_id {
+        if !device.matches_device_id(device_id) {
+            warn!(
+                "device ID 0x{:04X} does not belong to {}",
+                device_id, device.name
+            );
         }
     }
+
+    print_config(device, image);
 }
 
-fn read(port: &mut Box<dyn SerialPort>, verbose: bool) {
-    let serial_reader = SerialReader::new(vec![0; 1000], verbose);
-    let serial_writer = SerialWriter::new(verbose);
-    let mut serial_programmer = SerialProgrammer::new(serial_reader, serial_writer, verbose);
+fn save(image: &MemoryImage, output_file_path: &String) -> Result<(), ProgrammerError> {
+    image.write_hex(output_file_path).map_err(|e| {
+        ProgrammerError::File(format!(
+            "Failed to write \"{}\". Error: {}",
+            output_file_path, e
+        ))
+    })?;
+
+    info!("saved {} bytes to {}", image.bytes.len(), output_file_path);
 
-    serial_programmer.read(port);
+    Ok(())
 }
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+/// Limits how often the programmer may ask for a record again. `per_line` bounds the resends of a
+/// single record and `per_session` the resends of a whole programming session. The n-th resend of a
+/// record waits `delay * backoff^(n - 1)` before sending it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResendPolicy {
+    pub per_line: u32,
+    pub per_session: u32,
+    pub delay: Duration,
+    pub backoff: u32,
+}
+
+impl ResendPolicy {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.backoff.saturating_pow(attempt.saturating_sub(1));
+        self.delay.saturating_mul(factor)
+    }
+}
+
+impl Default for ResendPolicy {
+    fn default() -> ResendPolicy {
+        ResendPolicy {
+            per_line: 5,
+            per_session: 50,
+            delay: Duration::ZERO,
+            backoff: 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::programmer::resend_policy::ResendPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn given_backoff_should_grow_delay_with_every_attempt() {
+        let policy = ResendPolicy {
+            delay: Duration::from_millis(50),
+            backoff: 2,
+            ..ResendPolicy::default()
+        };
+
+        let delays: Vec<Duration> = (1..=4).map(|attempt| policy.delay(attempt)).collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(50),
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_no_delay_should_never_wait() {
+        assert_eq!(ResendPolicy::default().delay(10), Duration::ZERO);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::info;
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::serial_writer::WriteSerial;
+use serialport::{ClearBuffer, SerialPort};
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+/// Instruction answered with the ready message by a programmer that is already running.
+pub const PING_INSTRUCTION: u8 = b'H';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetLine {
+    Dtr,
+    Rts,
+}
+
+impl fmt::Display for ResetLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResetLine::Dtr => write!(f, "DTR"),
+            ResetLine::Rts => write!(f, "RTS"),
+        }
+    }
+}
+
+/// Level of the reset line during the pulse: `ActiveHigh` asserts the line for the pulse, which is
+/// what the auto-reset circuit of an Arduino expects, `ActiveLow` releases it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetPolarity {
+    ActiveHigh,
+    ActiveLow,
+}
+
+impl fmt::Display for ResetPolarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResetPolarity::ActiveHigh => write!(f, "active-high"),
+            ResetPolarity::ActiveLow => write!(f, "active-low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetPulse {
+    pub line: ResetLine,
+    pub polarity: ResetPolarity,
+    pub duration: Duration,
+}
+
+impl ResetPulse {
+    /// Idle and active level of the line, the line is set to idle, held active for the duration
+    /// of the pulse and set back to idle.
+    fn levels(&self) -> (bool, bool) {
+        let active = self.polarity == ResetPolarity::ActiveHigh;
+        (!active, active)
+    }
+}
+
+/// How the programmer is brought to send its ready message once the port is open. `Auto` relies
+/// on the board resetting when the port opens, `Pulse` toggles a modem line for boards without
+/// auto-reset and `Ping` asks a programmer that is already running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reset {
+    #[default]
+    Auto,
+    Pulse(ResetPulse),
+    Ping,
+}
+
+impl Reset {
+    /// Resets the programmer on `port`, the ping is sent through `writer`.
+    pub fn apply<W: WriteSerial>(
+        &self,
+        port: &mut Box<dyn SerialPort>,
+        writer: &mut W,
+    ) -> Result<(), ProgrammerError> {
+        match self {
+            Reset::Auto => Ok(()),
+            Reset::Pulse(pulse) => {
+                info!(
+                    "pulsing {} ({}) for {} ms",
+                    pulse.line,
+                    pulse.polarity,
+                    pulse.duration.as_millis()
+                );
+                let (idle, active) = pulse.levels();
+                set_line(port, pulse.line, idle)?;
+                set_line(port, pulse.line, active)?;
+                thread::sleep(pulse.duration);
+                set_line(port, pulse.line, idle)?;
+                port.clear(ClearBuffer::Input).map_err(serial_error)
+            }
+            Reset::Ping => {
+                info!("pinging programmer");
+                writer.write(port, &[PING_INSTRUCTION])
+            }
+        }
+    }
+}
+
+fn set_line(
+    port: &mut Box<dyn SerialPort>,
+    line: ResetLine,
+    level: bool,
+) -> Result<(), ProgrammerError> {
+    match line {
+        ResetLine::Dtr => port.write_data_terminal_ready(level),
+        ResetLine::Rts => port.write_request_to_send(level),
+    }
+    .map_err(serial_error)
+}
+
+fn serial_error(error: serialport::Error) -> ProgrammerError {
+    ProgrammerError::Serial(io::Error::from(error))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::programmer::reset::{ResetLine, ResetPolarity, ResetPulse};
+    use std::time::Duration;
+
+    #[test]
+    fn given_polarity_should_pulse_line_to_active_level() {
+        let pulse = |polarity| ResetPulse {
+            line: ResetLine::Dtr,
+            polarity,
+            duration: Duration::from_millis(100),
+        };
+
+        assert_eq!(pulse(ResetPolarity::ActiveHigh).levels(), (false, true));
+        assert_eq!(pulse(ResetPolarity::ActiveLow).levels(), (true, false));
+    }
+}
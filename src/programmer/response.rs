@@ -0,0 +1,275 @@
+use std::fmt;
+
+pub const READY_MESSAGE: &str = "Programmer ready!";
+pub const STARTED_MESSAGE: &str = "start";
+pub const DONE_MESSAGE: &str = "done";
+pub const OK_INSTRUCTION: char = 'Y';
+pub const RESEND_INSTRUCTION: char = 'R';
+pub const ERROR_PREFIX: char = 'E';
+const RECENT_CHARS: usize = 32;
+
+/// Failure reported by the programmer with an `E<code>` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    AddressOutOfRange,
+    VerifyFailed,
+    WriteTimeout,
+    Unknown(u16),
+}
+
+impl ErrorCode {
+    pub fn from_code(code: u16) -> ErrorCode {
+        match code {
+            1 => ErrorCode::AddressOutOfRange,
+            2 => ErrorCode::VerifyFailed,
+            3 => ErrorCode::WriteTimeout,
+            _ => ErrorCode::Unknown(code),
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            ErrorCode::AddressOutOfRange => 1,
+            ErrorCode::VerifyFailed => 2,
+            ErrorCode::WriteTimeout => 3,
+            ErrorCode::Unknown(code) => *code,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::AddressOutOfRange => write!(f, "address out of range for the target device"),
+            ErrorCode::VerifyFailed => write!(f, "written data did not verify"),
+            ErrorCode::WriteTimeout => write!(f, "device write timed out"),
+            ErrorCode::Unknown(_) => write!(f, "unknown error"),
+        }
+    }
+}
+
+/// A complete message from the programmer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Ack,
+    Resend,
+    Ready,
+    Start,
+    Done,
+    Error(ErrorCode),
+    Line(String),
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Ack => write!(f, "{}", OK_INSTRUCTION),
+            Response::Resend => write!(f, "{}", RESEND_INSTRUCTION),
+            Response::Ready => write!(f, "{}", READY_MESSAGE),
+            Response::Start => write!(f, "{}", STARTED_MESSAGE),
+            Response::Done => write!(f, "{}", DONE_MESSAGE),
+            Response::Error(code) => write!(f, "{}{}", ERROR_PREFIX, code.code()),
+            Response::Line(line) => write!(f, "{}", line),
+        }
+    }
+}
+
+/// Splits the data received from the programmer into responses. Status messages and any other
+/// output are newline terminated lines, a line made of `E` and a decimal number is an error code.
+/// `Y` and `R` are single bytes that are only taken as an answer when they start a line and are
+/// followed by a line terminator, or by nothing once the programmer went quiet (see [`idle`]), so
+/// debug text such as `Ready` or `Yes` stays a line even when it arrives split after its first
+/// byte. [`holds_lone_instruction`] tells when it is worth checking whether more data follows.
+///
+/// [`idle`]: ResponseParser::idle
+/// [`holds_lone_instruction`]: ResponseParser::holds_lone_instruction
+#[derive(Debug, Default)]
+pub struct ResponseParser {
+    buffer: String,
+    recent: String,
+}
+
+impl ResponseParser {
+    pub fn push(&mut self, data: &str) {
+        self.buffer.push_str(data);
+        self.recent.push_str(data);
+
+        let skipped = self.recent.chars().count().saturating_sub(RECENT_CHARS);
+        self.recent = self.recent.chars().skip(skipped).collect();
+    }
+
+    /// The last characters received, used to report what the programmer sent before a failure.
+    pub fn recent(&self) -> &str {
+        &self.recent
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.recent.clear();
+    }
+
+    pub fn next_response(&mut self) -> Option<Response> {
+        self.skip_line_terminators();
+
+        let mut chars = self.buffer.chars();
+        let first = chars.next()?;
+        if let Some('\r' | '\n') = chars.next() {
+            if let Some(response) = self.take_instruction(first) {
+                return Some(response);
+            }
+        }
+
+        let index = self.buffer.find('\n')?;
+        let line: String = self.buffer.drain(..=index).collect();
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        Some(match line {
+            READY_MESSAGE => Response::Ready,
+            STARTED_MESSAGE => Response::Start,
+            DONE_MESSAGE => Response::Done,
+            _ => match parse_error_code(line) {
+                Some(code) => Response::Error(ErrorCode::from_code(code)),
+                None => Response::Line(line.to_string()),
+            },
+        })
+    }
+}
+
+impl ResponseParser {
+    /// Whether the data received so far is only a `Y` or `R`, which is an answer unless more text
+    /// follows.
+    pub fn holds_lone_instruction(&self) -> bool {
+        let buffer = self.buffer.trim_start_matches(['\r', '\n']);
+        let mut chars = buffer.chars();
+        matches!(
+            (chars.next(), chars.next()),
+            (Some(OK_INSTRUCTION | RESEND_INSTRUCTION), None)
+        )
+    }
+
+    /// Called once no more data follows: a `Y` or `R` left alone at the end of the received data
+    /// is then the answer rather than the start of a line.
+    pub fn idle(&mut self) -> Option<Response> {
+        if !self.holds_lone_instruction() {
+            return None;
+        }
+
+        self.skip_line_terminators();
+        let first = self.buffer.chars().next()?;
+        self.take_instruction(first)
+    }
+
+    fn skip_line_terminators(&mut self) {
+        let start = self.buffer.len() - self.buffer.trim_start_matches(['\r', '\n']).len();
+        self.buffer.drain(..start);
+    }
+
+    fn take_instruction(&mut self, first: char) -> Option<Response> {
+        let response = match first {
+            OK_INSTRUCTION => Response::Ack,
+            RESEND_INSTRUCTION => Response::Resend,
+            _ => return None,
+        };
+        self.buffer.drain(..1);
+        Some(response)
+    }
+}
+
+fn parse_error_code(line: &str) -> Option<u16> {
+    let digits = line.strip_prefix(ERROR_PREFIX)?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::programmer::response::{ErrorCode, Response, ResponseParser};
+
+    fn parse(chunks: &[&str]) -> Vec<Response> {
+        let mut parser = ResponseParser::default();
+        let mut responses = vec![];
+        for chunk in chunks {
+            parser.push(chunk);
+            while let Some(response) = parser.next_response() {
+                responses.push(response);
+            }
+            // An empty chunk stands for a read that returned no data.
+            if chunk.is_empty() {
+                responses.extend(parser.idle());
+            }
+        }
+        responses
+    }
+
+    #[test]
+    fn given_status_lines_in_chunks_should_wait_for_line_end() {
+        assert_eq!(
+            parse(&["Programmer ", "ready", "!\r", "\nstart\r\n"]),
+            vec![Response::Ready, Response::Start]
+        );
+    }
+
+    #[test]
+    fn given_ack_bytes_with_terminator_or_before_idle_read_should_recognize_them() {
+        assert_eq!(
+            parse(&["Y", "", "R\r\n", "Y\ndone\r\n"]),
+            vec![
+                Response::Ack,
+                Response::Resend,
+                Response::Ack,
+                Response::Done
+            ]
+        );
+    }
+
+    #[test]
+    fn given_debug_text_with_ack_letters_should_keep_it_as_line() {
+        assert_eq!(
+            parse(&["Ready to write\r\n", "BYTE 0x3F\r\n", "write done\n"]),
+            vec![
+                Response::Line(String::from("Ready to write")),
+                Response::Line(String::from("BYTE 0x3F")),
+                Response::Line(String::from("write done")),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_debug_text_split_after_ack_letter_should_keep_it_as_line() {
+        assert_eq!(
+            parse(&["R", "eady to write\r\n", "B", "YTE 0x3F\r\n", "Y", "es\n"]),
+            vec![
+                Response::Line(String::from("Ready to write")),
+                Response::Line(String::from("BYTE 0x3F")),
+                Response::Line(String::from("Yes")),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_error_lines_should_decode_codes() {
+        assert_eq!(
+            parse(&["E1\r\n", "E02\n", "E42\n", "E\n", "Erasing\n"]),
+            vec![
+                Response::Error(ErrorCode::AddressOutOfRange),
+                Response::Error(ErrorCode::VerifyFailed),
+                Response::Error(ErrorCode::Unknown(42)),
+                Response::Line(String::from("E")),
+                Response::Line(String::from("Erasing")),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_more_data_than_reported_should_keep_only_recent_characters() {
+        let mut parser = ResponseParser::default();
+        parser.push(&"x".repeat(40));
+        parser.push("tail");
+
+        assert_eq!(parser.recent().len(), 32);
+        assert!(parser.recent().ends_with("xtail"));
+    }
+}
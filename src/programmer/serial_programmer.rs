@@ -1,166 +1,449 @@
+use crate::hex::hex_file::HexFile;
+use crate::hex::record::RecordType;
+use crate::programmer::capabilities::{Capabilities, Requirements};
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::progress::Progress;
+use crate::programmer::resend_policy::ResendPolicy;
+use crate::programmer::response::{Response, ResponseParser};
 use crate::programmer::serial_reader::ReadSerial;
 use crate::programmer::serial_writer::WriteSerial;
+use crate::programmer::timeouts::{Phase, Timeouts};
+use crate::{debug, info, warn};
 use serialport::SerialPort;
-use std::fs::File;
-use std::io::{BufReader, Lines};
-use std::str;
-
-const READY_MESSAGE: &'static str = "Programmer ready!";
-const STARTED_MESSAGE: &'static str = "start";
-const DONE_MESSAGE: &'static str = "done";
-const END_OF_FILE: &'static str = ":00000001FF";
-const OK_INSTRUCTION: u8 = b'Y';
-const RESEND_INSTRUCTION: u8 = b'R';
+use std::thread;
+use std::time::{Duration, Instant};
+
 const PROGRAM_INSTRUCTION: u8 = b'P';
 const READ_STORED_PROGRAM_INSTRUCTION: u8 = b'D';
+const ERASE_INSTRUCTION: u8 = b'E';
+const IDENTIFY_INSTRUCTION: u8 = b'I';
+/// How long a lone `Y` or `R` waits for text following it before it is taken as the answer. The
+/// rest of a debug line such as `Ready` arrives within a few byte times.
+const ANSWER_LOOKAHEAD: Duration = Duration::from_millis(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EraseMode {
+    Bulk,
+    Program,
+    Eeprom,
+}
+
+impl EraseMode {
+    fn code(&self) -> u8 {
+        match self {
+            EraseMode::Bulk => b'B',
+            EraseMode::Program => b'P',
+            EraseMode::Eeprom => b'D',
+        }
+    }
+}
 
 pub struct SerialProgrammer<R: ReadSerial, W: WriteSerial> {
     reader: R,
     writer: W,
-    verbose: bool,
+    timeouts: Timeouts,
+    resend_policy: ResendPolicy,
+    requirements: Option<Requirements>,
+    capabilities: Option<Capabilities>,
+    parser: ResponseParser,
+    session_started: Instant,
+    progress: bool,
 }
 
 impl<R: ReadSerial, W: WriteSerial> SerialProgrammer<R, W> {
-    pub fn new(reader: R, writer: W, verbose: bool) -> SerialProgrammer<R, W> {
+    pub fn new(reader: R, writer: W) -> SerialProgrammer<R, W> {
         SerialProgrammer {
             reader,
             writer,
-            verbose,
+            timeouts: Timeouts::default(),
+            resend_policy: ResendPolicy::default(),
+            requirements: None,
+            capabilities: None,
+            parser: ResponseParser::default(),
+            session_started: Instant::now(),
+            progress: true,
         }
     }
 
-    pub fn program(&mut self, port: &mut Box<dyn SerialPort>, lines: Lines<BufReader<File>>) {
-        self.wait_for_programmer_message(port, READY_MESSAGE, &mut String::new());
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> SerialProgrammer<R, W> {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn with_resend_policy(mut self, resend_policy: ResendPolicy) -> SerialProgrammer<R, W> {
+        self.resend_policy = resend_policy;
+        self
+    }
+
+    /// Shows the progress of programming while the console shows info but not debug messages.
+    pub fn with_progress(mut self, progress: bool) -> SerialProgrammer<R, W> {
+        self.progress = progress;
+        self
+    }
 
-        self.send_lines(port, lines);
+    /// Identifies the firmware after the ready message of every session and refuses to continue
+    /// when it does not meet the requirements.
+    pub fn with_handshake(mut self, requirements: Requirements) -> SerialProgrammer<R, W> {
+        self.requirements = Some(requirements);
+        self
+    }
 
-        println!("[CLI] finished programming!")
+    /// Capabilities reported by the firmware in the last handshake.
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
     }
 
-    pub fn read(&mut self, port: &mut Box<dyn SerialPort>) {
-        self.wait_for_programmer_message(port, READY_MESSAGE, &mut String::new());
+    pub fn identify(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+    ) -> Result<Capabilities, ProgrammerError> {
+        self.start_session();
+        self.wait_for(port, Response::Ready, Phase::Ready)?;
 
-        self.read_contents(port);
+        let capabilities = self.query_capabilities(port)?;
+        self.capabilities = Some(capabilities.clone());
 
-        println!("[CLI] finished reading contents!")
+        Ok(capabilities)
     }
 
-    fn wait_for_programmer_message(
+    pub fn program(
         &mut self,
         port: &mut Box<dyn SerialPort>,
-        message: &str,
-        rest_data: &mut String,
-    ) {
-        let mut received_data = String::new();
-        println!("[CLI] waiting for programmer for '{}'....", message);
+        hex_file: &HexFile,
+    ) -> Result<(), ProgrammerError> {
+        self.start_session();
+        self.wait_for(port, Response::Ready, Phase::Ready)?;
+
+        let longest_line = hex_file
+            .lines
+            .iter()
+            .map(|line| line.record.to_string().len())
+            .max()
+            .unwrap_or(0);
+        self.handshake(port, longest_line)?;
+
+        self.send_lines(port, hex_file)?;
+
+        info!("finished programming!");
+
+        Ok(())
+    }
+
+    pub fn read(&mut self, port: &mut Box<dyn SerialPort>) -> Result<Vec<String>, ProgrammerError> {
+        self.start_session();
+        self.wait_for(port, Response::Ready, Phase::Ready)?;
+        self.handshake(port, 0)?;
+
+        let lines = self.read_contents(port)?;
+
+        info!("finished reading contents!");
+
+        Ok(lines)
+    }
+
+    /// Reads the stored program right after programming, the programmer is already past its ready message.
+    pub fn read_back(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+    ) -> Result<Vec<String>, ProgrammerError> {
+        let lines = self.read_contents(port)?;
+
+        info!("finished reading back contents!");
+
+        Ok(lines)
+    }
+
+    pub fn erase(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        mode: EraseMode,
+    ) -> Result<(), ProgrammerError> {
+        self.start_session();
+        self.wait_for(port, Response::Ready, Phase::Ready)?;
+        self.handshake(port, 0)?;
+
+        info!("{:?} erase started", mode);
+        self.writer.write(port, &[ERASE_INSTRUCTION, mode.code()])?;
+        self.wait_for(port, Response::Start, Phase::Start)?;
+        self.wait_for(port, Response::Done, Phase::Done)?;
+
+        info!("finished erasing!");
+
+        Ok(())
+    }
+
+    fn start_session(&mut self) {
+        self.session_started = Instant::now();
+        self.capabilities = None;
+        self.parser.clear();
+    }
+
+    fn handshake(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        longest_line: usize,
+    ) -> Result<(), ProgrammerError> {
+        let Some(requirements) = self.requirements.clone() else {
+            return Ok(());
+        };
+
+        let capabilities = self.query_capabilities(port)?;
+        capabilities
+            .check(&requirements, longest_line)
+            .map_err(ProgrammerError::Incompatible)?;
+        self.capabilities = Some(capabilities);
+
+        Ok(())
+    }
+
+    fn query_capabilities(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+    ) -> Result<Capabilities, ProgrammerError> {
+        info!("identifying firmware");
+        let lines = self.read_lines(port, IDENTIFY_INSTRUCTION)?;
+        let capabilities = Capabilities::parse(&lines).map_err(ProgrammerError::Protocol)?;
+
+        for line in capabilities.to_string().lines() {
+            info!("firmware {}", line);
+        }
+
+        Ok(capabilities)
+    }
+
+    /// Waits for `expected`, printing any other output. Until the ready message arrives everything
+    /// else is skipped as the programmer may still be booting, afterwards another answer or status
+    /// message is a protocol error.
+    fn wait_for(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        expected: Response,
+        phase: Phase,
+    ) -> Result<(), ProgrammerError> {
+        info!("waiting for programmer for '{}'....", expected);
+        let phase_started = Instant::now();
         loop {
-            self.reader.read(port, &mut received_data);
-
-            if received_data.contains(message) {
-                if let Some(index) = received_data.find('\n') {
-                    let content = received_data[..index].to_string();
-                    *rest_data = received_data[index + 1..].to_string();
-                    println!("[Programmer]: '{}'", content);
-                } else {
-                    println!("[Programmer]: '{}'", received_data);
+            match self.next_response(port, phase, phase_started)? {
+                response if response == expected => {
+                    info!(programmer: "'{}'", response);
+                    return Ok(());
                 }
-
-                break;
+                Response::Line(line) => {
+                    info!(programmer: "'{}'", line);
+                    self.check_deadline(phase, phase_started)?;
+                }
+                _ if phase == Phase::Ready => {}
+                response => return Err(unexpected(response, phase)),
             }
         }
     }
 
-    fn send_lines(&mut self, port: &mut Box<dyn SerialPort>, lines: Lines<BufReader<File>>) {
-        let mut received_data = String::new();
+    fn send_lines(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        hex_file: &HexFile,
+    ) -> Result<(), ProgrammerError> {
         let mut programming_message_sent = false;
-        let ok_instruction_string = &OK_INSTRUCTION.to_string();
-        let resend_instruction_string = &RESEND_INSTRUCTION.to_string();
-
-        for line in lines {
-            let string = line.unwrap();
-            let trimmed_line = string.trim();
-            if trimmed_line.is_empty() {
-                continue;
-            }
+        let mut session_resends = 0;
+        let mut progress = Progress::new(hex_file, self.progress);
+
+        for line in &hex_file.lines {
+            let encoded_record = line.record.to_string();
 
             if !programming_message_sent {
-                println!("[CLI] programming started");
-                self.writer.write(port, &PROGRAM_INSTRUCTION.to_be_bytes());
-                self.wait_for_programmer_message(port, STARTED_MESSAGE, &mut received_data);
+                info!("programming started");
+                self.writer
+                    .write(port, &PROGRAM_INSTRUCTION.to_be_bytes())?;
+                self.wait_for(port, Response::Start, Phase::Start)?;
 
                 programming_message_sent = true;
             }
 
-            self.writer.write(port, trimmed_line.as_bytes());
-
-            let mut instruction_sent_correctly = false;
-            received_data.clear();
-            while !instruction_sent_correctly {
-                self.reader.read(port, &mut received_data);
+            self.writer.write(port, encoded_record.as_bytes())?;
 
-                if self.verbose {
-                    println!("[Programmer] received data: '{}'", received_data);
-                }
+            let phase = Phase::Ack { line: line.number };
+            let mut phase_started = Instant::now();
+            let mut line_resends = 0;
+            loop {
+                match self.next_response(port, phase, phase_started)? {
+                    Response::Ack => {
+                        progress.record_sent();
+                        break;
+                    }
+                    Response::Resend => {
+                        let line_limit_reached = line_resends >= self.resend_policy.per_line;
+                        if line_limit_reached || session_resends >= self.resend_policy.per_session {
+                            return Err(ProgrammerError::ResendLimit {
+                                line: line.number,
+                                record: encoded_record,
+                                line_resends,
+                                session_resends,
+                                session: !line_limit_reached,
+                            });
+                        }
+                        line_resends += 1;
+                        session_resends += 1;
+                        progress.resent();
 
-                if received_data.contains(resend_instruction_string) {
-                    println!("[CLI] resending instruction {}", trimmed_line);
-                    self.writer.write(port, trimmed_line.as_bytes());
-                    received_data.clear();
-                } else if received_data.contains(ok_instruction_string) {
-                    instruction_sent_correctly = true;
+                        let delay = self.resend_policy.delay(line_resends);
+                        if !delay.is_zero() {
+                            thread::sleep(delay);
+                        }
+                        progress.clear();
+                        warn!(
+                            "resending instruction {} (line {}, attempt {} of {})",
+                            encoded_record, line.number, line_resends, self.resend_policy.per_line
+                        );
+                        self.writer.write(port, encoded_record.as_bytes())?;
+                        phase_started = Instant::now();
+                    }
+                    Response::Line(text) => {
+                        progress.clear();
+                        info!(programmer: "'{}'", text);
+                        self.check_deadline(phase, phase_started)?;
+                    }
+                    response => return Err(unexpected(response, phase)),
                 }
             }
 
-            if trimmed_line.contains(END_OF_FILE) {
-                println!("[CLI] end of file reached {}", trimmed_line);
+            if line.record.record_type == RecordType::EndOfFile {
+                progress.finish();
+                debug!("end of file reached {}", encoded_record);
                 break;
             }
         }
 
         if programming_message_sent {
-            self.wait_for_programmer_message(port, DONE_MESSAGE, &mut received_data);
+            self.wait_for(port, Response::Done, Phase::Done)?;
         }
+
+        Ok(())
     }
 
-    fn read_contents(&mut self, port: &mut Box<dyn SerialPort>) {
-        let mut received_data = String::new();
-        println!("[CLI] read started");
-        self.writer
-            .write(port, &READ_STORED_PROGRAM_INSTRUCTION.to_be_bytes());
-        self.wait_for_programmer_message(port, STARTED_MESSAGE, &mut received_data);
+    fn read_contents(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+    ) -> Result<Vec<String>, ProgrammerError> {
+        info!("read started");
+        self.read_lines(port, READ_STORED_PROGRAM_INSTRUCTION)
+    }
 
-        'read_loop: loop {
-            while let Some(index) = received_data.find('\n') {
-                let content = received_data[..index].to_string();
-                let second_part = received_data[index + 1..].to_string();
+    /// Sends `instruction` and collects the lines the programmer sends between `start` and `done`.
+    fn read_lines(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        instruction: u8,
+    ) -> Result<Vec<String>, ProgrammerError> {
+        let mut lines = vec![];
+        self.writer.write(port, &instruction.to_be_bytes())?;
+        self.wait_for(port, Response::Start, Phase::Start)?;
 
-                received_data = second_part;
+        let phase_started = Instant::now();
+        loop {
+            match self.next_response(port, Phase::Done, phase_started)? {
+                Response::Done => break,
+                Response::Line(content) => {
+                    info!(programmer: "{}", content);
+                    lines.push(content.trim().to_string());
+                    self.check_deadline(Phase::Done, phase_started)?;
+                }
+                response => return Err(unexpected(response, Phase::Done)),
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Reads from the port until the parser has a complete response or the deadline passes, which
+    /// is checked before every attempt so a programmer that keeps talking is cut off as well. Error
+    /// codes sent by the programmer end the session.
+    fn next_response(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        phase: Phase,
+        phase_started: Instant,
+    ) -> Result<Response, ProgrammerError> {
+        loop {
+            self.check_deadline(phase, phase_started)?;
+            if let Some(response) = self.parser.next_response() {
+                return check_error(response, phase);
+            }
 
-                println!("[Programmer]: {}", content);
+            let mut received_data = String::new();
+            self.reader.read(port, &mut received_data)?;
+            self.parser.push(&received_data);
 
-                if received_data.contains(DONE_MESSAGE) || content.contains(DONE_MESSAGE) {
-                    break 'read_loop;
+            if let Some(response) = self.parser.next_response() {
+                return check_error(response, phase);
+            }
+            if self.parser.holds_lone_instruction()
+                && !self.reader.data_follows(port, ANSWER_LOOKAHEAD)?
+            {
+                if let Some(response) = self.parser.idle() {
+                    return check_error(response, phase);
                 }
             }
+        }
+    }
+
+    /// Fails once the phase or the whole session ran out of time, reporting the last data received.
+    fn check_deadline(&self, phase: Phase, phase_started: Instant) -> Result<(), ProgrammerError> {
+        let phase_limit = self.timeouts.of(phase);
+        let (limit, overall) = match self.timeouts.overall {
+            _ if phase_started.elapsed() >= phase_limit => (phase_limit, false),
+            Some(overall) if self.session_started.elapsed() >= overall => (overall, true),
+            _ => return Ok(()),
+        };
 
-            self.reader.read(port, &mut received_data);
+        Err(ProgrammerError::Timeout {
+            phase,
+            limit,
+            overall,
+            received: self.parser.recent().to_string(),
+        })
+    }
+}
+
+/// Error codes are ignored before the ready message like any other output of a booting programmer.
+fn check_error(response: Response, phase: Phase) -> Result<Response, ProgrammerError> {
+    match response {
+        Response::Error(code) if phase != Phase::Ready => {
+            Err(ProgrammerError::Programmer { code, phase })
         }
+        response => Ok(response),
     }
 }
 
+fn unexpected(response: Response, phase: Phase) -> ProgrammerError {
+    ProgrammerError::Protocol(format!(
+        "unexpected '{}' while waiting for {}",
+        response, phase
+    ))
+}
+
 #[cfg(test)]
 mod test {
-    use crate::programmer::file_reader::get_lines;
-    use crate::programmer::serial_programmer::{
-        SerialProgrammer, DONE_MESSAGE, END_OF_FILE, OK_INSTRUCTION, READY_MESSAGE,
-        RESEND_INSTRUCTION, STARTED_MESSAGE,
+    use crate::hex::hex_file::HexFile;
+    use crate::log;
+    use crate::log::{Source, DEFAULT_LEVEL};
+    use crate::programmer::capabilities::Requirements;
+    use crate::programmer::error::ProgrammerError;
+    use crate::programmer::resend_policy::ResendPolicy;
+    use crate::programmer::reset::Reset;
+    use crate::programmer::response::{
+        DONE_MESSAGE, OK_INSTRUCTION, READY_MESSAGE, RESEND_INSTRUCTION, STARTED_MESSAGE,
     };
+    use crate::programmer::serial_programmer::{EraseMode, SerialProgrammer};
     use crate::programmer::serial_reader::ReadSerial;
     use crate::programmer::serial_writer::WriteSerial;
     use crate::programmer::test_serial_port::TestSerialPort;
+    use crate::programmer::timeouts::{Phase, Timeouts};
     use serialport::SerialPort;
     use std::env;
     use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    const END_OF_FILE_RECORD: &str = ":00000001FF";
 
     #[test]
     fn given_starting_message_in_multiple_chunks_and_empty_file_should_not_write_anything() {
@@ -168,144 +451,150 @@ mod test {
             data: vec![
                 String::from("Programmer "),
                 String::from("ready"),
-                String::from("!"),
+                String::from("!\n"),
             ],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/empty-file.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/empty-file.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
-        assert_eq!(programmer.writer.data.is_empty(), true);
+        assert!(programmer.writer.data.is_empty());
     }
 
     #[test]
     fn given_all_starting_message_and_empty_file_should_not_write_anything() {
         let reader = ReaderTest {
-            data: vec![String::from("Programmer ready!")],
+            data: vec![String::from("Programmer ready!\n")],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/empty-file.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/empty-file.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
-        assert_eq!(programmer.writer.data.is_empty(), true);
+        assert!(programmer.writer.data.is_empty());
     }
 
     #[test]
     fn given_file_with_multiple_blank_lines_should_not_write_anything() {
         let reader = ReaderTest {
-            data: vec![String::from("Programmer ready!")],
+            data: vec![String::from("Programmer ready!\n")],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/empty-file.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/multiple-blank-lines.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
-        assert_eq!(programmer.writer.data.is_empty(), true);
+        assert!(programmer.writer.data.is_empty());
     }
 
     #[test]
     fn given_file_with_only_eof_should_write_one_instruction() {
         let reader = ReaderTest {
             data: vec![
-                String::from(READY_MESSAGE),
-                String::from(STARTED_MESSAGE),
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
                 OK_INSTRUCTION.to_string(),
-                String::from(DONE_MESSAGE),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
             ],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/only-eof.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
         assert_eq!(programmer.writer.data[0], "P");
-        assert_eq!(programmer.writer.data[1], END_OF_FILE);
+        assert_eq!(programmer.writer.data[1], END_OF_FILE_RECORD);
+    }
+
+    #[test]
+    fn given_no_reset_should_ping_before_waiting_for_ready() {
+        let reader = ReaderTest {
+            data: vec![
+                String::new(),
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let mut writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+
+        Reset::Ping.apply(&mut port, &mut writer).unwrap();
+        let mut programmer = SerialProgrammer::new(reader, writer);
+        programmer.program(&mut port, &hex_file).unwrap();
+
+        assert_eq!(programmer.writer.data, vec!["H", "P", END_OF_FILE_RECORD]);
+        assert_eq!(programmer.reader.index, 6);
     }
 
     #[test]
     fn given_file_eof_at_the_beginning_should_write_one_instruction() {
         let reader = ReaderTest {
             data: vec![
-                String::from(READY_MESSAGE),
-                String::from(STARTED_MESSAGE),
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
                 OK_INSTRUCTION.to_string(),
-                String::from(DONE_MESSAGE),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
             ],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/eof-at-the-beginning.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/eof-at-the-beginning.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
         assert_eq!(programmer.writer.data[0], "P");
-        assert_eq!(programmer.writer.data[1], END_OF_FILE);
+        assert_eq!(programmer.writer.data[1], END_OF_FILE_RECORD);
     }
 
     #[test]
     fn given_file_with_all_instructions_sent_correctly_should_write_all_instructions_once() {
         let reader = ReaderTest {
             data: vec![
-                String::from("Programmer ready!"),
-                String::from(STARTED_MESSAGE),
+                String::from("Programmer ready!\n"),
+                format!("{}\n", STARTED_MESSAGE),
                 OK_INSTRUCTION.to_string(),
+                String::new(),
                 OK_INSTRUCTION.to_string(),
+                String::new(),
                 OK_INSTRUCTION.to_string(),
-                String::from(DONE_MESSAGE),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
             ],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/multiple-instructions.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
         assert_eq!(programmer.writer.data[0], "P");
-        assert_eq!(programmer.writer.data[1], ":02002400BE1C");
-        assert_eq!(programmer.writer.data[2], ":02002500BE1B");
-        assert_eq!(programmer.writer.data[3], END_OF_FILE);
+        assert_eq!(programmer.writer.data[1], ":02002400BE1C00");
+        assert_eq!(programmer.writer.data[2], ":02002500BE1B00");
+        assert_eq!(programmer.writer.data[3], END_OF_FILE_RECORD);
     }
 
     #[test]
@@ -313,44 +602,574 @@ mod test {
     ) {
         let reader = ReaderTest {
             data: vec![
-                String::from("Programmer ready!"),
-                String::from(STARTED_MESSAGE),
+                String::from("Programmer ready!\n"),
+                format!("{}\n", STARTED_MESSAGE),
                 RESEND_INSTRUCTION.to_string(),
+                String::new(),
                 OK_INSTRUCTION.to_string(),
+                String::new(),
                 OK_INSTRUCTION.to_string(),
+                String::new(),
                 OK_INSTRUCTION.to_string(),
-                String::from(DONE_MESSAGE),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
             ],
             index: 0,
         };
         let writer = WriterTest { data: vec![] };
         let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
-        let lines = get_lines(
-            &get_full_path("test-files/multiple-instructions.hex")
-                .to_string_lossy()
-                .to_string(),
-        );
-        let mut programmer = SerialProgrammer::new(reader, writer, true);
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
 
-        programmer.program(&mut port, lines);
+        programmer.program(&mut port, &hex_file).unwrap();
 
         assert_eq!(programmer.writer.data[0], "P");
-        assert_eq!(programmer.writer.data[1], ":02002400BE1C");
-        assert_eq!(programmer.writer.data[2], ":02002400BE1C");
-        assert_eq!(programmer.writer.data[3], ":02002500BE1B");
-        assert_eq!(programmer.writer.data[4], END_OF_FILE);
+        assert_eq!(programmer.writer.data[1], ":02002400BE1C00");
+        assert_eq!(programmer.writer.data[2], ":02002400BE1C00");
+        assert_eq!(programmer.writer.data[3], ":02002500BE1B00");
+        assert_eq!(programmer.writer.data[4], END_OF_FILE_RECORD);
     }
 
+    #[test]
+    fn given_stored_program_should_return_lines_received_before_done() {
+        let reader = ReaderTest {
+            data: vec![
+                String::from("Programmer ready!\n"),
+                String::from("start\n"),
+                String::from(":02000000FF3FC0\n:000000"),
+                String::from("01FF\n"),
+                String::from("done\n"),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        let lines = programmer.read(&mut port).unwrap();
+
+        assert_eq!(programmer.writer.data[0], "D");
+        assert_eq!(lines, vec![":02000000FF3FC0", END_OF_FILE_RECORD]);
+    }
+
+    #[test]
+    fn given_programmed_file_should_read_back_on_the_same_connection() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                String::from("done\n"),
+                String::from("start\n"),
+                String::from(":00000001FF\n"),
+                String::from("done\n"),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        programmer.program(&mut port, &hex_file).unwrap();
+        let lines = programmer.read_back(&mut port).unwrap();
+
+        assert_eq!(programmer.writer.data, vec!["P", END_OF_FILE_RECORD, "D"]);
+        assert_eq!(lines, vec![END_OF_FILE_RECORD]);
+    }
+
+    #[test]
+    fn given_read_should_log_every_line_at_default_level() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from(":02002400BE1C00\n:00000001FF\n"),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        let messages = log::capture(|| {
+            programmer.read(&mut port).unwrap();
+        });
+
+        let shown: Vec<&str> = messages
+            .iter()
+            .filter(|(level, source, _)| *level <= DEFAULT_LEVEL && *source == Source::Programmer)
+            .map(|(_, _, message)| message.as_str())
+            .collect();
+        assert!(shown.contains(&":02002400BE1C00"));
+        assert!(shown.contains(&END_OF_FILE_RECORD));
+    }
+
+    #[test]
+    fn given_erase_mode_should_send_erase_instruction_with_mode() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        programmer.erase(&mut port, EraseMode::Eeprom).unwrap();
+
+        assert_eq!(programmer.writer.data, vec!["ED"]);
+    }
+
+    #[test]
+    fn given_record_rejected_too_often_should_abort_reporting_the_record() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer =
+            SerialProgrammer::new(reader, writer).with_resend_policy(ResendPolicy {
+                per_line: 2,
+                ..ResendPolicy::default()
+            });
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        assert!(matches!(
+            result,
+            Err(ProgrammerError::ResendLimit {
+                line: 1,
+                line_resends: 2,
+                session: false,
+                ..
+            })
+        ));
+        assert_eq!(
+            programmer.writer.data,
+            vec!["P", ":02002400BE1C00", ":02002400BE1C00", ":02002400BE1C00"]
+        );
+    }
+
+    #[test]
+    fn given_resends_spread_over_lines_should_abort_at_session_limit() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+                RESEND_INSTRUCTION.to_string(),
+                String::new(),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer =
+            SerialProgrammer::new(reader, writer).with_resend_policy(ResendPolicy {
+                per_session: 2,
+                ..ResendPolicy::default()
+            });
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        match result {
+            Err(error @ ProgrammerError::ResendLimit { .. }) => assert_eq!(
+                error.to_string(),
+                "session resend limit of 2 reached, record :02002500BE1B00 (line 2) kept failing after 1 resend(s), 2 in the session"
+            ),
+            _ => panic!("expected the resend limit to be reached"),
+        }
+    }
+
+    #[test]
+    fn given_debug_output_between_acks_should_not_be_taken_as_answer() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from("Ready to write BYTE 0x24\r\n"),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                String::from("Y\r\n"),
+                format!("{}\r\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        programmer.program(&mut port, &hex_file).unwrap();
+
+        assert_eq!(
+            programmer.writer.data,
+            vec![
+                "P",
+                ":02002400BE1C00",
+                ":02002500BE1B00",
+                END_OF_FILE_RECORD
+            ]
+        );
+    }
+
+    #[test]
+    fn given_debug_line_split_after_ack_letter_should_wait_for_the_rest() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from("R"),
+                String::from("eady to write\r\n"),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        programmer.program(&mut port, &hex_file).unwrap();
+
+        assert_eq!(programmer.writer.data, vec!["P", END_OF_FILE_RECORD]);
+    }
+
+    #[test]
+    fn given_status_message_instead_of_ack_should_fail_with_protocol_error() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        match result {
+            Err(error @ ProgrammerError::Protocol(_)) => assert_eq!(
+                error.to_string(),
+                "protocol error: unexpected 'done' while waiting for the acknowledgement of line 1"
+            ),
+            _ => panic!("expected a protocol error"),
+        }
+    }
+
+    #[test]
+    fn given_error_code_instead_of_ack_should_abort_with_readable_error() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                String::from("E2\r\n"),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/multiple-instructions.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer);
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        match result {
+            Err(error @ ProgrammerError::Programmer { .. }) => {
+                assert_eq!(
+                    error.to_string(),
+                    "programmer reported E2 (written data did not verify) while waiting for the acknowledgement of line 2"
+                );
+                assert_eq!(error.exit_code(), 7);
+            }
+            _ => panic!("expected a programmer error"),
+        }
+        assert_eq!(programmer.writer.data.len(), 3);
+    }
+
+    #[test]
+    fn given_handshake_should_identify_firmware_before_programming() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from("version=2.0\ncommands=PDI\nmax-line=44\n"),
+                format!("{}\n", DONE_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                OK_INSTRUCTION.to_string(),
+                String::new(),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer = SerialProgrammer::new(reader, writer).with_handshake(Requirements {
+            commands: vec!['P'],
+            family: None,
+        });
+
+        programmer.program(&mut port, &hex_file).unwrap();
+
+        assert_eq!(programmer.writer.data, vec!["I", "P", END_OF_FILE_RECORD]);
+        assert_eq!(programmer.capabilities().unwrap().version, "2.0");
+    }
+
+    #[test]
+    fn given_firmware_without_required_command_should_refuse_to_proceed() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from("version=1.0\ncommands=PD\n"),
+                format!("{}\n", DONE_MESSAGE),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer = SerialProgrammer::new(reader, writer).with_handshake(Requirements {
+            commands: vec!['E'],
+            family: None,
+        });
+
+        let result = programmer.erase(&mut port, EraseMode::Bulk);
+
+        assert!(matches!(result, Err(ProgrammerError::Incompatible(_))));
+        assert_eq!(programmer.writer.data, vec!["I"]);
+    }
+
+    #[test]
+    fn given_programmer_not_acknowledging_line_should_fail_naming_the_line() {
+        let reader = ReaderTest {
+            data: vec![
+                format!("{}\n", READY_MESSAGE),
+                format!("{}\n", STARTED_MESSAGE),
+                String::from("garbage"),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer =
+            SerialProgrammer::new(reader, writer).with_timeouts(short_timeouts(None));
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        match result {
+            Err(ProgrammerError::Timeout {
+                phase,
+                overall,
+                received,
+                ..
+            }) => {
+                assert_eq!(phase, Phase::Ack { line: 1 });
+                assert!(!overall);
+                assert!(received.ends_with("garbage"));
+            }
+            _ => panic!("expected a timeout"),
+        }
+        assert_eq!(programmer.writer.data, vec!["P", END_OF_FILE_RECORD]);
+    }
+
+    #[test]
+    fn given_read_without_done_should_fail_waiting_for_done() {
+        let reader = ReaderTest {
+            data: vec![
+                String::from("Programmer ready!\n"),
+                String::from("start\n"),
+                String::from(":00000001FF\n:0200"),
+            ],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer =
+            SerialProgrammer::new(reader, writer).with_timeouts(short_timeouts(None));
+
+        let result = programmer.read(&mut port);
+
+        assert!(matches!(
+            result,
+            Err(ProgrammerError::Timeout { phase: Phase::Done, received, .. }) if received.ends_with("\n:0200")
+        ));
+    }
+
+    #[test]
+    fn given_programmer_sending_lines_forever_should_fail_at_the_deadline() {
+        let reader = ChattyReader {
+            reader: ReaderTest {
+                data: vec![String::from("Programmer ready!\n"), String::from("start\n")],
+                index: 0,
+            },
+            line: ":00000001FF\n",
+        };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut programmer = SerialProgrammer::new(reader, WriterTest { data: vec![] })
+            .with_timeouts(short_timeouts(None));
+
+        let result = programmer.read(&mut port);
+
+        assert!(matches!(
+            result,
+            Err(ProgrammerError::Timeout {
+                phase: Phase::Done,
+                overall: false,
+                ..
+            })
+        ));
+
+        let reader = ChattyReader {
+            reader: ReaderTest {
+                data: vec![String::from("Programmer ready!\n"), String::from("start\n")],
+                index: 0,
+            },
+            line: "writing\n",
+        };
+        let hex_file = read_hex_file("test-files/only-eof.hex");
+        let mut programmer = SerialProgrammer::new(reader, WriterTest { data: vec![] })
+            .with_timeouts(short_timeouts(None));
+
+        let result = programmer.program(&mut port, &hex_file);
+
+        assert!(matches!(
+            result,
+            Err(ProgrammerError::Timeout {
+                phase: Phase::Ack { line: 1 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn given_overall_timeout_shorter_than_phase_should_fail_with_overall_timeout() {
+        let reader = ReaderTest {
+            data: vec![],
+            index: 0,
+        };
+        let writer = WriterTest { data: vec![] };
+        let mut port: Box<dyn SerialPort> = Box::new(TestSerialPort {});
+        let mut timeouts = short_timeouts(Some(Duration::from_millis(10)));
+        timeouts.ready = Duration::from_secs(60);
+        let mut programmer = SerialProgrammer::new(reader, writer).with_timeouts(timeouts);
+
+        let result = programmer.erase(&mut port, EraseMode::Bulk);
+
+        assert!(matches!(
+            result,
+            Err(ProgrammerError::Timeout {
+                phase: Phase::Ready,
+                overall: true,
+                ..
+            })
+        ));
+        assert!(programmer.writer.data.is_empty());
+    }
+
+    fn short_timeouts(overall: Option<Duration>) -> Timeouts {
+        Timeouts {
+            ready: Duration::from_millis(10),
+            start: Duration::from_millis(10),
+            ack: Duration::from_millis(10),
+            done: Duration::from_millis(10),
+            overall,
+            detect: Duration::from_millis(10),
+        }
+    }
+
+    /// Returns one chunk per read. An empty chunk is a read that got no data, as after a bare `Y` or
+    /// `R` while the programmer waits for the next line.
     struct ReaderTest {
         data: Vec<String>,
         index: usize,
     }
 
     impl ReadSerial for ReaderTest {
-        fn read(&mut self, _port: &mut Box<dyn SerialPort>, received_data: &mut String) {
-            let string = self.data[self.index].as_str();
+        fn read(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            received_data: &mut String,
+        ) -> Result<(), ProgrammerError> {
+            let Some(string) = self.data.get(self.index) else {
+                return Ok(());
+            };
             self.index += 1;
             received_data.push_str(string);
+            Ok(())
+        }
+
+        fn data_follows(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            _window: Duration,
+        ) -> Result<bool, ProgrammerError> {
+            Ok(self
+                .data
+                .get(self.index)
+                .is_some_and(|string| !string.is_empty()))
+        }
+    }
+
+    /// Plays `reader`, then sends `line` on every read like a programmer that never stops talking.
+    struct ChattyReader {
+        reader: ReaderTest,
+        line: &'static str,
+    }
+
+    impl ReadSerial for ChattyReader {
+        fn read(
+            &mut self,
+            port: &mut Box<dyn SerialPort>,
+            received_data: &mut String,
+        ) -> Result<(), ProgrammerError> {
+            if self.reader.index < self.reader.data.len() {
+                return self.reader.read(port, received_data);
+            }
+            received_data.push_str(self.line);
+            Ok(())
+        }
+
+        fn data_follows(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            _window: Duration,
+        ) -> Result<bool, ProgrammerError> {
+            Ok(true)
         }
     }
 
@@ -359,16 +1178,25 @@ mod test {
     }
 
     impl WriteSerial for WriterTest {
-        fn write(&mut self, _port: &mut Box<dyn SerialPort>, buffer: &[u8]) {
-            self.data.push(String::from_utf8_lossy(buffer).to_string())
+        fn write(
+            &mut self,
+            _port: &mut Box<dyn SerialPort>,
+            buffer: &[u8],
+        ) -> Result<(), ProgrammerError> {
+            self.data.push(String::from_utf8_lossy(buffer).to_string());
+            Ok(())
         }
     }
 
     fn get_full_path(relative_path: &str) -> PathBuf {
         let current_dir = env::current_dir().expect("Failed to get current directory");
-        let full_path = current_dir
+        current_dir
             .join(Path::new("src/programmer"))
-            .join(Path::new(relative_path));
-        full_path
+            .join(Path::new(relative_path))
+    }
+
+    fn read_hex_file(relative_path: &str) -> HexFile {
+        HexFile::read(&get_full_path(relative_path).to_string_lossy())
+            .expect("Failed to parse test hex file")
     }
 }
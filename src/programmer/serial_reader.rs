@@ -1,41 +1,80 @@
+use crate::programmer::error::ProgrammerError;
+use crate::trace;
 use serialport::SerialPort;
-use std::{io, str};
+use std::time::{Duration, Instant};
+use std::{io, str, thread};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
 
 pub struct SerialReader {
     serial_buf: Vec<u8>,
-    verbose: bool,
 }
 
 pub trait ReadSerial {
-    fn read(&mut self, port: &mut Box<dyn SerialPort>, received_data: &mut String);
+    fn read(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        received_data: &mut String,
+    ) -> Result<(), ProgrammerError>;
+
+    /// Whether more data arrives within `window`, without reading it.
+    fn data_follows(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        window: Duration,
+    ) -> Result<bool, ProgrammerError>;
 }
 
 impl SerialReader {
-    pub fn new(serial_buf: Vec<u8>, verbose: bool) -> SerialReader {
-        SerialReader {
-            serial_buf,
-            verbose,
-        }
+    pub fn new(serial_buf: Vec<u8>) -> SerialReader {
+        SerialReader { serial_buf }
     }
 }
 
 impl ReadSerial for SerialReader {
-    fn read(&mut self, port: &mut Box<dyn SerialPort>, received_data: &mut String) {
+    fn read(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        received_data: &mut String,
+    ) -> Result<(), ProgrammerError> {
         match port.read(self.serial_buf.as_mut_slice()) {
             Ok(bytes_read) => {
                 let content = &self.serial_buf[..bytes_read];
+                let chunk = str::from_utf8(content).map_err(|_| {
+                    ProgrammerError::Protocol(format!(
+                        "unable to convert content to string: {:02X?}",
+                        content
+                    ))
+                })?;
 
-                if let Ok(chunk) = str::from_utf8(content) {
-                    if self.verbose {
-                        println!("[Programmer] raw data: '{}'", chunk);
-                    }
-                    received_data.push_str(chunk);
-                } else {
-                    panic!("[CLI] unable to convert content to string")
+                if !chunk.is_empty() {
+                    trace!(programmer: "raw data: '{}'", chunk);
                 }
+                received_data.push_str(chunk);
+                Ok(())
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => Ok(()),
+            Err(e) => Err(ProgrammerError::Serial(e)),
+        }
+    }
+
+    fn data_follows(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        window: Duration,
+    ) -> Result<bool, ProgrammerError> {
+        let started = Instant::now();
+        loop {
+            let pending = port
+                .bytes_to_read()
+                .map_err(|error| ProgrammerError::Serial(io::Error::from(error)))?;
+            if pending > 0 {
+                return Ok(true);
+            }
+            if started.elapsed() >= window {
+                return Ok(false);
             }
-            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
-            Err(e) => panic!("{:?}", e),
+            thread::sleep(POLL_INTERVAL);
         }
     }
 }
@@ -1,27 +1,37 @@
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::timeouts::Phase;
+use crate::{debug, trace};
 use serialport::SerialPort;
 use std::io;
 use std::io::Write;
 
-pub struct SerialWriter {
-    verbose: bool,
-}
+#[derive(Default)]
+pub struct SerialWriter {}
 
 pub trait WriteSerial {
-    fn write(&mut self, port: &mut Box<dyn SerialPort>, buffer: &[u8]);
+    fn write(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        buffer: &[u8],
+    ) -> Result<(), ProgrammerError>;
 }
 
 impl SerialWriter {
-    pub fn new(verbose: bool) -> SerialWriter {
-        SerialWriter { verbose }
+    pub fn new() -> SerialWriter {
+        SerialWriter {}
     }
 }
 
 impl WriteSerial for SerialWriter {
-    fn write(&mut self, port: &mut Box<dyn SerialPort>, buffer: &[u8]) {
+    fn write(
+        &mut self,
+        port: &mut Box<dyn SerialPort>,
+        buffer: &[u8],
+    ) -> Result<(), ProgrammerError> {
         let mut bytes_written = 0;
         let buffer_len = buffer.len();
-        println!(
-            "[CLI] writing: '{}' ({} bytes)",
+        debug!(
+            "writing: '{}' ({} bytes)",
             String::from_utf8_lossy(buffer),
             buffer.len()
         );
@@ -30,16 +40,23 @@ impl WriteSerial for SerialWriter {
             match port.write(&buffer[bytes_written..bytes_written + 1]) {
                 Ok(n) => {
                     bytes_written += n;
-                    if self.verbose {
-                        println!("[CLI] bytes written '{}', total {}", n, bytes_written);
-                    }
+                    trace!("bytes written '{}', total {}", n, bytes_written);
                 }
-                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {}
-                Err(e) => {
-                    eprintln!("Error writing to serial port: {:?}", e);
-                    break;
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
+                    return Err(ProgrammerError::Timeout {
+                        phase: Phase::Write {
+                            written: bytes_written,
+                            total: buffer_len,
+                        },
+                        limit: port.timeout(),
+                        overall: false,
+                        received: String::new(),
+                    });
                 }
+                Err(e) => return Err(ProgrammerError::Serial(e)),
             }
         }
+
+        Ok(())
     }
 }
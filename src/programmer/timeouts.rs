@@ -0,0 +1,66 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Step of a session with the programmer that is bounded by a deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Ready,
+    Start,
+    Ack { line: usize },
+    Done,
+    Write { written: usize, total: usize },
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Ready => write!(f, "the ready message"),
+            Phase::Start => write!(f, "the start message"),
+            Phase::Ack { line } => write!(f, "the acknowledgement of line {}", line),
+            Phase::Done => write!(f, "the done message"),
+            Phase::Write { written, total } => write!(
+                f,
+                "the serial port write ({} of {} bytes written)",
+                written, total
+            ),
+        }
+    }
+}
+
+/// Deadlines for each phase, measured from the moment the phase starts waiting, so output sent
+/// while waiting does not extend them. While reading, the done deadline bounds the whole read.
+/// `overall` bounds the whole session when set. `detect` is how long each port probed by port detection may take to send the
+/// ready message, kept short so ports without a programmer are skipped quickly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    pub ready: Duration,
+    pub start: Duration,
+    pub ack: Duration,
+    pub done: Duration,
+    pub overall: Option<Duration>,
+    pub detect: Duration,
+}
+
+impl Timeouts {
+    pub fn of(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Ready => self.ready,
+            Phase::Start => self.start,
+            Phase::Ack { .. } => self.ack,
+            Phase::Done | Phase::Write { .. } => self.done,
+        }
+    }
+}
+
+impl Default for Timeouts {
+    fn default() -> Timeouts {
+        Timeouts {
+            ready: Duration::from_millis(10000),
+            start: Duration::from_millis(5000),
+            ack: Duration::from_millis(5000),
+            done: Duration::from_millis(30000),
+            overall: None,
+            detect: Duration::from_millis(2500),
+        }
+    }
+}
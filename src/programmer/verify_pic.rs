@@ -0,0 +1,61 @@
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::{MemoryImage, WordMismatch};
+use crate::programmer::capabilities::Requirements;
+use crate::programmer::connection::Connection;
+use crate::programmer::error::ProgrammerError;
+use crate::programmer::read_pic::image_from_lines;
+use crate::{error, info};
+
+pub fn verify_pic(
+    input_file_path: &String,
+    connection: &Connection,
+) -> Result<(), ProgrammerError> {
+    info!("reading file {}", input_file_path);
+    let expected = MemoryImage::from_hex_file(&HexFile::read(input_file_path)?);
+
+    let port_name = &connection.port_name;
+    let baud_rate = connection.baud_rate;
+    info!(
+        "verifying file '{0}' against Arduino connected at {1} with baud rate {2}...",
+        input_file_path, port_name, baud_rate
+    );
+
+    let mut port = connection.open()?;
+    let requirements = Requirements {
+        commands: vec!['D'],
+        family: None,
+    };
+    let lines = connection.programmer(requirements).read(&mut port)?;
+    verify(&expected, &lines)
+}
+
+/// Compares the lines read back from the programmer with the expected image and fails on any difference.
+pub fn verify(expected: &MemoryImage, lines: &[String]) -> Result<(), ProgrammerError> {
+    let actual = image_from_lines(lines)?;
+
+    let mismatches = expected.compare_words(&actual);
+    if mismatches.is_empty() {
+        info!("verification passed, {} bytes match", expected.bytes.len());
+        return Ok(());
+    }
+
+    print_mismatches(&mismatches);
+    Err(ProgrammerError::Verification(format!(
+        "verification failed, {} word(s) differ",
+        mismatches.len()
+    )))
+}
+
+fn print_mismatches(mismatches: &[WordMismatch]) {
+    for mismatch in mismatches {
+        let actual = match mismatch.actual {
+            Some(actual) => format!("0x{:04X}", actual),
+            None => String::from("missing"),
+        };
+
+        error!(
+            "mismatch at 0x{:06X}: expected 0x{:04X}, actual {}",
+            mismatch.address, mismatch.expected, actual
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use crate::config_word::print_config;
+use crate::device::Device;
+use crate::hex::hex_file::HexFile;
+use crate::hex::memory_image::MemoryImage;
+use crate::info;
+use crate::programmer::error::ProgrammerError;
+
+pub fn show_config(input_file_path: &String, device: &Device) -> Result<(), ProgrammerError> {
+    info!("reading file {}", input_file_path);
+    let hex_file = HexFile::read(input_file_path)?;
+
+    info!("target device {}", device);
+    print_config(device, &MemoryImage::from_hex_file(&hex_file));
+    Ok(())
+}
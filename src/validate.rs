@@ -0,0 +1,58 @@
+use crate::device::Device;
+use crate::hex::hex_file::HexFile;
+use crate::hex::validation::validate;
+use crate::info;
+use crate::programmer::error::ProgrammerError;
+
+pub fn validate_file(
+    input_file_path: &String,
+    device: Option<&'static Device>,
+) -> Result<(), ProgrammerError> {
+    info!("validating file {}", input_file_path);
+    if let Some(device) = device {
+        info!("target device {}", device);
+    }
+
+    let hex_file = HexFile::read(input_file_path)
+        .map_err(|errors| problems(errors.iter().map(|error| error.to_string()).collect()))?;
+
+    let memory = device.map_or(vec![], |device| device.writable_ranges());
+    let issues = validate(&hex_file, &memory);
+    if issues.is_empty() {
+        info!(
+            "{} is valid ({} records)",
+            input_file_path,
+            hex_file.lines.len()
+        );
+        return Ok(());
+    }
+
+    Err(problems(
+        issues
+            .iter()
+            .map(|issue| format!("{}: {}", input_file_path, issue))
+            .collect(),
+    ))
+}
+
+fn problems(mut messages: Vec<String>) -> ProgrammerError {
+    messages.push(format!("{} problem(s) found", messages.len()));
+    ProgrammerError::File(messages.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::validate::validate_file;
+
+    #[test]
+    fn given_invalid_file_should_fail_with_file_exit_code() {
+        let missing = validate_file(&String::from("src/programmer/test-files/missing.hex"), None);
+        let empty = validate_file(
+            &String::from("src/programmer/test-files/empty-file.hex"),
+            None,
+        );
+
+        assert_eq!(missing.unwrap_err().exit_code(), 4);
+        assert_eq!(empty.unwrap_err().exit_code(), 4);
+    }
+}